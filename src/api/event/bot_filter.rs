@@ -0,0 +1,70 @@
+use crate::BOT_PATTERNS;
+
+/// Detects bots and crawlers by their User-Agent, so their events aren't sent.
+///
+/// A User-Agent is a bot if it contains any of `patterns` and none of `allowed`,
+/// ignoring case. Patterns starting with `^` only match at the start of the User-Agent, e.g.
+/// `^whatsapp/` matches the link previewer of `WhatsApp` but not browsers that mention it.
+#[derive(Debug, Clone)]
+pub struct BotFilter {
+    /// Lowercase User-Agent substrings of bots.
+    ///
+    /// Defaults to `BOT_PATTERNS`.
+    pub patterns: Vec<String>,
+
+    /// Lowercase User-Agent substrings that are never treated as bots, even if they match
+    /// one of `patterns`.
+    pub allowed: Vec<String>,
+}
+
+impl BotFilter {
+    /// Create a new filter using the bundled `BOT_PATTERNS`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_patterns(BOT_PATTERNS)
+    }
+
+    /// Create a new filter using the given patterns instead of `BOT_PATTERNS`.
+    #[must_use]
+    pub fn with_patterns<S: AsRef<str>>(patterns: &[S]) -> Self {
+        Self {
+            patterns: patterns.iter().map(|p| p.as_ref().to_lowercase()).collect(),
+            allowed: Vec::new(),
+        }
+    }
+
+    /// Treat User-Agents containing `pattern` as bots.
+    pub fn deny(&mut self, pattern: &str) -> &mut Self {
+        self.patterns.push(pattern.to_lowercase());
+        self
+    }
+
+    /// Never treat User-Agents containing `pattern` as bots.
+    pub fn allow(&mut self, pattern: &str) -> &mut Self {
+        self.allowed.push(pattern.to_lowercase());
+        self
+    }
+
+    /// Returns whether the User-Agent belongs to a bot.
+    #[must_use]
+    pub fn is_bot(&self, user_agent: &str) -> bool {
+        let user_agent: String = user_agent.to_lowercase();
+        !self.allowed.iter().any(|p| matches(&user_agent, p))
+            && self.patterns.iter().any(|p| matches(&user_agent, p))
+    }
+}
+
+/// Returns whether the lowercase `user_agent` matches `pattern`.
+fn matches(user_agent: &str, pattern: &str) -> bool {
+    match pattern.strip_prefix('^') {
+        Some(prefix) => user_agent.starts_with(prefix),
+        None => user_agent.contains(pattern),
+    }
+}
+
+impl Default for BotFilter {
+    /// Defaults to `Self::new()`.
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,69 @@
+/// Lowercase User-Agent substrings of known bots, headless browsers, uptime monitors, HTTP
+/// libraries and link previewers.
+///
+/// Patterns starting with `^` only match at the start of the User-Agent, see `BotFilter`.
+/// Keep this list sorted within each group when adding new patterns.
+pub const BOT_PATTERNS: &[&str] = &[
+    // crawlers
+    "adsbot",
+    "ahrefs",
+    "baiduspider",
+    "bingpreview",
+    "bot)",
+    "bot-",
+    "bot/",
+    "bot;",
+    "bytespider",
+    "ccbot",
+    "crawl",
+    "feedfetcher",
+    "google-inspectiontool",
+    "googleother",
+    "mediapartners-google",
+    "petalbot",
+    "scrapy",
+    "semrush",
+    "slurp",
+    "spider",
+    // headless browsers and automation
+    "cypress",
+    "headless",
+    "lighthouse",
+    "phantomjs",
+    "playwright",
+    "puppeteer",
+    "selenium",
+    "webdriver",
+    // uptime monitors
+    "better uptime",
+    "checkly",
+    "datadog",
+    "newrelicpinger",
+    "pingdom",
+    "site24x7",
+    "statuscake",
+    "uptime-kuma",
+    "uptimerobot",
+    // link previewers
+    "^whatsapp/",
+    "embedly",
+    "facebookexternalhit",
+    "google web preview",
+    "iframely",
+    "skypeuripreview",
+    "slackbot",
+    "telegrambot",
+    "vkshare",
+    // http libraries
+    "axios/",
+    "curl/",
+    "go-http-client",
+    "httpclient",
+    "java/",
+    "libwww-perl",
+    "node-fetch",
+    "okhttp",
+    "python-requests",
+    "python-urllib",
+    "wget/",
+];
@@ -0,0 +1,26 @@
+/// What to do with events from visitors who sent a Do-Not-Track or Global Privacy Control
+/// signal.
+///
+/// See `PrivacySignals`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConsentPolicy {
+    /// Send the event as usual.
+    ///
+    /// `EventOutcome::is_consent_ignored` tells whether the visitor had opted out.
+    #[default]
+    Ignore,
+
+    /// Don't send the event.
+    Drop,
+
+    /// Send the event without the visitor's User-Agent and IP address.
+    ///
+    /// `ANONYMOUS_USER_AGENT` is sent instead of the User-Agent, and no X-Forwarded-For header is
+    /// sent.
+    /// Plausible can then no longer tell these visitors apart, so they are counted as a single
+    /// unique visitor.
+    Anonymize,
+}
+
+/// User-Agent sent in place of the visitor's for `ConsentPolicy::Anonymize`.
+pub const ANONYMOUS_USER_AGENT: &str = "Mozilla/5.0 (compatible; plausible-rs)";
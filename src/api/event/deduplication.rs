@@ -0,0 +1,166 @@
+use crate::hash::Fnv1a;
+use crate::{EventHeaders, EventPayload};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Default number of keys remembered by `InMemoryDedupStore`.
+pub const DEFAULT_DEDUP_CAPACITY: usize = 10_000;
+
+/// Suppresses repeats of the same event within a time window.
+///
+/// Each event is identified by `EventPayload::idempotency_key` if set, otherwise by a hash of
+/// its `EventHeaders` and `EventPayload` fields.
+#[derive(Debug, Clone)]
+pub struct Deduplication {
+    /// How long an event is remembered after it was sent.
+    pub window: Duration,
+
+    /// Where the keys of recently sent events are stored.
+    pub store: Arc<dyn DedupStore>,
+}
+
+impl Deduplication {
+    /// Deduplicate events within `window` using an `InMemoryDedupStore`.
+    #[must_use]
+    pub fn new(window: Duration) -> Self {
+        Self::with_store(window, Arc::new(InMemoryDedupStore::default()))
+    }
+
+    /// Deduplicate events within `window` using a custom `DedupStore`.
+    #[must_use]
+    pub fn with_store(window: Duration, store: Arc<dyn DedupStore>) -> Self {
+        Self { window, store }
+    }
+
+    /// Returns the key identifying this event.
+    #[must_use]
+    pub fn key(headers: &EventHeaders, payload: &EventPayload) -> String {
+        if let Some(idempotency_key) = &payload.idempotency_key {
+            return idempotency_key.clone();
+        }
+
+        let mut hasher = Fnv1a::new();
+        hasher.write_field(headers.user_agent.as_bytes());
+        hasher.write_field(headers.x_forwarded_for.as_bytes());
+        hasher.write_field(payload.domain.as_bytes());
+        hasher.write_field(payload.name.as_bytes());
+        hasher.write_field(payload.url.as_bytes());
+        hasher.write_field(payload.referrer.as_deref().unwrap_or_default().as_bytes());
+        hasher.write_field(&payload.screen_width.unwrap_or_default().to_le_bytes());
+
+        // props are sorted, as `HashMap` iteration order differs between instances
+        if let Some(props) = &payload.props {
+            let mut props: Vec<_> = props.iter().collect();
+            props.sort_by_key(|(key, _)| *key);
+            for (key, value) in props {
+                hasher.write_field(key.as_bytes());
+                hasher.write_field(format!("{value:?}").as_bytes());
+            }
+        }
+
+        format!("{:016x}", hasher.finish())
+    }
+}
+
+/// Storage for the keys of recently sent events.
+///
+/// Implement this to share deduplication state between processes, e.g. in Redis.
+pub trait DedupStore: Debug + Send + Sync {
+    /// Records `key` and returns `true` if it was not already recorded within the last `window`.
+    fn insert(&self, key: &str, window: Duration) -> bool;
+
+    /// Forgets `key`, e.g. because sending the event failed and should be retried.
+    fn remove(&self, key: &str);
+}
+
+/// Bounded in-memory `DedupStore`.
+///
+/// Once `capacity` keys are stored, the oldest key is forgotten to make room for a new one.
+///
+/// Keys expire according to `tokio::time::Instant`, so tests can advance time with
+/// `tokio::time::advance`.
+#[derive(Debug)]
+pub struct InMemoryDedupStore {
+    capacity: usize,
+    entries: Mutex<Entries>,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    inserted_at: HashMap<String, Instant>,
+    order: VecDeque<(String, Instant)>,
+}
+
+impl Entries {
+    fn pop_oldest(&mut self) {
+        if let Some((key, inserted_at)) = self.order.pop_front() {
+            // the key may have been removed, or re-inserted after this entry
+            if self.inserted_at.get(&key) == Some(&inserted_at) {
+                self.inserted_at.remove(&key);
+            }
+        }
+    }
+}
+
+impl InMemoryDedupStore {
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(Entries::default()),
+        }
+    }
+}
+
+impl Default for InMemoryDedupStore {
+    /// Defaults to `Self::new(DEFAULT_DEDUP_CAPACITY)`.
+    fn default() -> Self {
+        Self::new(DEFAULT_DEDUP_CAPACITY)
+    }
+}
+
+impl DedupStore for InMemoryDedupStore {
+    fn insert(&self, key: &str, window: Duration) -> bool {
+        let now = Instant::now();
+        let mut entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        // forget expired keys
+        while let Some((_, inserted_at)) = entries.order.front() {
+            if now.duration_since(*inserted_at) < window {
+                break;
+            }
+            entries.pop_oldest();
+        }
+
+        if let Some(inserted_at) = entries.inserted_at.get(key) {
+            if now.duration_since(*inserted_at) < window {
+                return false;
+            }
+        }
+
+        // make room for the new key
+        while entries.order.len() >= self.capacity.max(1) {
+            entries.pop_oldest();
+        }
+
+        entries.inserted_at.insert(key.to_string(), now);
+        entries.order.push_back((key.to_string(), now));
+        true
+    }
+
+    fn remove(&self, key: &str) {
+        let mut entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if entries.inserted_at.remove(key).is_some() {
+            entries.order.retain(|(order_key, _)| order_key != key);
+        }
+    }
+}
@@ -1,5 +1,12 @@
+use crate::{Error, PrivacySignals, TrustedProxies, forwarded_chain};
+use http::HeaderMap;
+use http::header::USER_AGENT;
+use http::request::Parts;
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr};
+
 /// Request headers for the 'POST /api/event' API.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventHeaders {
     /// The raw value of User-Agent is used to calculate the `user_id` which identifies a
     /// [unique visitor](https://plausible.io/data-policy#how-we-count-unique-users-without-cookies)
@@ -23,6 +30,12 @@ pub struct EventHeaders {
     /// More information can be found on
     /// [MDN docs](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/X-Forwarded-For).
     pub x_forwarded_for: String,
+
+    /// Do-Not-Track and Global Privacy Control signals sent by the visitor's browser.
+    ///
+    /// These are not sent to Plausible, but are checked against the client's `ConsentPolicy`.
+    #[serde(default)]
+    pub privacy_signals: PrivacySignals,
 }
 
 impl EventHeaders {
@@ -31,6 +44,73 @@ impl EventHeaders {
         Self {
             user_agent,
             x_forwarded_for,
+            privacy_signals: PrivacySignals::new(false, false),
         }
     }
+
+    /// Build headers from those of an incoming request.
+    ///
+    /// The client IP address is the first address of the RFC 7239 `Forwarded` header or, if it is
+    /// missing, of `X-Forwarded-For`, like Plausible does. Without either header, `peer`, the
+    /// address of the connection, is used.
+    /// The `DNT` and `Sec-GPC` headers are parsed into `PrivacySignals`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if no client IP address can be determined.
+    pub fn from_header_map(headers: &HeaderMap, peer: Option<IpAddr>) -> Result<Self, Error> {
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+
+        let client_ip: IpAddr = forwarded_chain(headers)
+            .first()
+            .copied()
+            .or(peer)
+            .ok_or(Error::MissingClientIp)?;
+
+        Ok(Self {
+            user_agent: header(USER_AGENT.as_str()).unwrap_or_default().to_string(),
+            x_forwarded_for: client_ip.to_string(),
+            privacy_signals: PrivacySignals::from_header_values(header("dnt"), header("sec-gpc")),
+        })
+    }
+
+    /// Build headers from those of an incoming request, picking the client IP address according to
+    /// `trusted_proxies` instead of taking the first address of the forwarding chain.
+    ///
+    /// See `Self::from_header_map`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if no client IP address can be determined.
+    pub fn from_header_map_trusting(
+        headers: &HeaderMap,
+        peer: Option<IpAddr>,
+        trusted_proxies: &TrustedProxies,
+    ) -> Result<Self, Error> {
+        let client_ip: IpAddr = trusted_proxies.client_ip(headers, peer)?;
+        let mut event_headers: Self = Self::from_header_map(headers, Some(client_ip))?;
+        event_headers.x_forwarded_for = client_ip.to_string();
+        Ok(event_headers)
+    }
+
+    /// Build headers from the head of an incoming request.
+    ///
+    /// If `peer` is `None`, a `SocketAddr` or `IpAddr` in the request's extensions is used as the
+    /// address of the connection. See `Self::from_header_map`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if no client IP address can be determined.
+    pub fn from_request_parts(parts: &Parts, peer: Option<IpAddr>) -> Result<Self, Error> {
+        let peer: Option<IpAddr> = peer
+            .or_else(|| parts.extensions.get::<SocketAddr>().map(SocketAddr::ip))
+            .or_else(|| parts.extensions.get::<IpAddr>().copied());
+        Self::from_header_map(&parts.headers, peer)
+    }
+
+    #[must_use]
+    pub const fn with_privacy_signals(mut self, privacy_signals: PrivacySignals) -> Self {
+        self.privacy_signals = privacy_signals;
+        self
+    }
 }
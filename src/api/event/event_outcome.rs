@@ -0,0 +1,126 @@
+use crate::{EventRequest, PrivacySignals};
+use bytes::Bytes;
+use reqwest::StatusCode;
+use reqwest::header::HeaderMap;
+
+/// Header Plausible sets on responses to events it received but ignored, e.g. because they came
+/// from a blocked IP address or were recorded for an unknown site.
+pub const DROPPED_HEADER: &str = "x-plausible-dropped";
+
+/// Outcome of recording an event with `Plausible::event`.
+#[derive(Debug, Clone)]
+pub enum EventOutcome {
+    /// The event was sent to Plausible, which recorded it.
+    Accepted(EventResponse),
+
+    /// The event was sent to Plausible, which ignored it.
+    ///
+    /// Plausible still responds with a success status code, but sets the `DROPPED_HEADER`.
+    Dropped(EventResponse),
+
+    /// The event was not sent because the client is in dry-run mode.
+    ///
+    /// Contains the request that would have been sent.
+    /// See `PlausibleBuilder::dry_run`.
+    DryRun(EventRequest),
+
+    /// The event was not sent because the visitor opted out of tracking.
+    ///
+    /// See `ConsentPolicy::Drop`.
+    ConsentWithheld,
+
+    /// The event was not sent because its page isn't tracked.
+    ///
+    /// See `UrlFilter`.
+    Excluded,
+
+    /// The event was not sent because its User-Agent belongs to a bot.
+    ///
+    /// See `BotFilter`.
+    BotFiltered,
+
+    /// The event was not sent because an identical event was sent recently.
+    ///
+    /// See `Deduplication`.
+    Deduplicated,
+
+    /// The event was not sent because it was sampled out.
+    ///
+    /// See `SamplingPolicy`.
+    Sampled,
+}
+
+impl EventOutcome {
+    /// Returns whether Plausible recorded the event.
+    #[must_use]
+    pub const fn is_accepted(&self) -> bool {
+        matches!(self, Self::Accepted(_))
+    }
+
+    /// Returns whether the event was, or in dry-run mode would have been, sent without the
+    /// visitor's User-Agent and IP address.
+    #[must_use]
+    pub const fn is_anonymized(&self) -> bool {
+        match self {
+            Self::Accepted(response) | Self::Dropped(response) => response.anonymized,
+            Self::DryRun(request) => request.anonymized,
+            _ => false,
+        }
+    }
+
+    /// Returns the privacy signals the visitor sent, if the event was, or in dry-run mode would
+    /// have been, sent.
+    #[must_use]
+    pub const fn privacy_signals(&self) -> Option<PrivacySignals> {
+        match self {
+            Self::Accepted(response) | Self::Dropped(response) => Some(response.privacy_signals),
+            Self::DryRun(request) => Some(request.privacy_signals),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the event was, or in dry-run mode would have been, sent as is although
+    /// the visitor opted out of tracking.
+    ///
+    /// See `ConsentPolicy::Ignore`.
+    #[must_use]
+    pub const fn is_consent_ignored(&self) -> bool {
+        match self.privacy_signals() {
+            Some(privacy_signals) => privacy_signals.opted_out() && !self.is_anonymized(),
+            None => false,
+        }
+    }
+
+    /// Returns Plausible's response, if the event was sent.
+    #[must_use]
+    pub const fn response(&self) -> Option<&EventResponse> {
+        match self {
+            Self::Accepted(response) | Self::Dropped(response) => Some(response),
+            _ => None,
+        }
+    }
+}
+
+/// Response returned from the 'POST /api/event' API, kept for debugging.
+#[derive(Debug, Clone)]
+pub struct EventResponse {
+    pub status_code: StatusCode,
+    pub headers: HeaderMap,
+    pub bytes: Bytes,
+
+    /// Whether the event was sent without the visitor's User-Agent and IP address.
+    ///
+    /// See `ConsentPolicy::Anonymize`.
+    pub anonymized: bool,
+
+    /// Privacy signals the visitor sent, which the client's `ConsentPolicy` was applied to.
+    pub privacy_signals: PrivacySignals,
+}
+
+impl EventResponse {
+    /// Returns whether Plausible ignored the event.
+    #[must_use]
+    pub fn is_dropped(&self) -> bool {
+        self.headers.contains_key(DROPPED_HEADER)
+    }
+}
@@ -1,4 +1,5 @@
-use crate::{EventPayloadBuilder, PropValue};
+use super::site_domain::{push_domain, split_domains};
+use crate::{Error, EventPayloadBuilder, PropValue, SiteDomain};
 use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
@@ -11,6 +12,11 @@ pub struct EventPayload {
     /// This is the domain name you used when you added your site to your Plausible account.
     /// It doesn't need to be an actual domain name, so when adding your mobile app to Plausible,
     /// you could insert the mobile app name in the domain name field
+    ///
+    /// To record the event for several sites at once, e.g. for a
+    /// [roll-up site](https://plausible.io/docs/plausible-script#can-i-send-stats-to-multiple-dashboards-at-the-same-time),
+    /// separate their domain names with commas. See `Self::builder_for_domains` and
+    /// `Self::add_domain`.
     pub domain: String,
 
     /// Name of the event.
@@ -71,6 +77,29 @@ pub struct EventPayload {
     /// Data structures such as objects, arrays etc. aren't accepted.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub props: Option<HashMap<String, PropValue>>,
+
+    /// Whether the site uses hash-based routing, e.g. `https://example.com/#/login`.
+    ///
+    /// Plausible discards the fragment of `url` unless this is set, in which case it is shown as
+    /// part of the page in the dashboard.
+    /// When using the script, this is set by the `hash` script extension.
+    ///
+    /// See: <https://plausible.io/docs/hash-based-routing>
+    #[serde(
+        rename = "h",
+        default,
+        skip_serializing_if = "std::ops::Not::not",
+        with = "hash_mode"
+    )]
+    pub hash_mode: bool,
+
+    /// Key identifying this event for deduplication.
+    ///
+    /// Events sharing a key are only sent once within the client's `Deduplication` window.
+    /// When unset, the key is derived from the event's headers and payload.
+    /// It is never sent to Plausible.
+    #[serde(skip)]
+    pub idempotency_key: Option<String>,
 }
 
 impl EventPayload {
@@ -90,6 +119,8 @@ impl EventPayload {
             referrer,
             screen_width,
             props,
+            hash_mode: false,
+            idempotency_key: None,
         }
     }
 
@@ -97,4 +128,72 @@ impl EventPayload {
     pub const fn builder(domain: String, name: String, url: String) -> EventPayloadBuilder {
         EventPayloadBuilder::new(domain, name, url)
     }
+
+    /// Create a builder recording the event for each of the validated site `domains`.
+    ///
+    /// See `EventPayloadBuilder::from_domains`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `domains` is empty.
+    pub fn builder_for_domains(
+        domains: &[SiteDomain],
+        name: String,
+        url: String,
+    ) -> Result<EventPayloadBuilder, Error> {
+        EventPayloadBuilder::from_domains(domains, name, url)
+    }
+
+    /// Returns the domain names of all sites this event is recorded for.
+    pub fn domains(&self) -> impl Iterator<Item = &str> {
+        split_domains(&self.domain)
+    }
+
+    /// Also record this event for the site `domain`, unless it already is.
+    pub fn add_domain(&mut self, domain: &SiteDomain) {
+        push_domain(&mut self.domain, domain);
+    }
+
+    /// Create a builder, validating the site domain and URL.
+    ///
+    /// See `EventPayloadBuilder::try_new`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `domain` is not a valid `SiteDomain`, or if `url` is relative or
+    /// can't be the URL of a page.
+    pub fn try_builder(
+        domain: &str,
+        name: String,
+        url: &str,
+    ) -> Result<EventPayloadBuilder, Error> {
+        EventPayloadBuilder::try_new(domain, name, url)
+    }
+}
+
+/// (De)serializes `EventPayload::hash_mode` as `1`, the way the script sends it.
+mod hash_mode {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Flag {
+        Bool(bool),
+        Number(u8),
+    }
+
+    #[expect(
+        clippy::trivially_copy_pass_by_ref,
+        reason = "serde passes fields by reference"
+    )]
+    pub fn serialize<S: Serializer>(hash_mode: &bool, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(u8::from(*hash_mode))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+        Ok(match Flag::deserialize(deserializer)? {
+            Flag::Bool(hash_mode) => hash_mode,
+            Flag::Number(hash_mode) => hash_mode != 0,
+        })
+    }
 }
@@ -1,5 +1,7 @@
-use crate::{EventPayload, PropValue};
+use super::site_domain::push_domain;
+use crate::{Error, EventPayload, PropValue, SiteDomain, Utm};
 use std::collections::HashMap;
+use url::Url;
 
 /// Request body parameters for the 'POST /api/event' API.
 ///
@@ -11,6 +13,10 @@ pub struct EventPayloadBuilder {
     /// This is the domain name you used when you added your site to your Plausible account.
     /// It doesn't need to be an actual domain name, so when adding your mobile app to Plausible,
     /// you could insert the mobile app name in the domain name field
+    ///
+    /// To record the event for several sites at once, e.g. for a
+    /// [roll-up site](https://plausible.io/docs/plausible-script#can-i-send-stats-to-multiple-dashboards-at-the-same-time),
+    /// separate their domain names with commas. See `Self::from_domains` and `Self::add_domain`.
     pub domain: String,
 
     /// Name of the event.
@@ -68,6 +74,27 @@ pub struct EventPayloadBuilder {
     /// Custom properties only accepts scalar values such as strings, numbers and booleans.
     /// Data structures such as objects, arrays etc. aren't accepted.
     pub props: Option<HashMap<String, PropValue>>,
+
+    /// Whether the site uses hash-based routing, e.g. `https://example.com/#/login`.
+    ///
+    /// Plausible discards the fragment of `url` unless this is set, in which case it is shown as
+    /// part of the page in the dashboard.
+    /// When using the script, this is set by the `hash` script extension.
+    ///
+    /// See: <https://plausible.io/docs/hash-based-routing>
+    pub hash_mode: bool,
+
+    /// Key identifying this event for deduplication.
+    ///
+    /// Events sharing a key are only sent once within the client's `Deduplication` window.
+    /// When unset, the key is derived from the event's headers and payload.
+    /// It is never sent to Plausible.
+    pub idempotency_key: Option<String>,
+
+    /// UTM parameters encoded into `url` when the payload is built.
+    ///
+    /// They replace any UTM parameters already in `url`.
+    pub utm: Option<Utm>,
 }
 
 impl EventPayloadBuilder {
@@ -80,7 +107,67 @@ impl EventPayloadBuilder {
             referrer: None,
             screen_width: None,
             props: None,
+            hash_mode: false,
+            idempotency_key: None,
+            utm: None,
+        }
+    }
+
+    /// Create a new builder from a validated site domain and URL.
+    ///
+    /// Logs a warning if the host of `url` clearly belongs to a different site than `domain`,
+    /// as Plausible would then likely ignore the event.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `url` can't be the URL of a page, e.g. `mailto:` URLs.
+    pub fn from_url(domain: SiteDomain, name: String, url: &Url) -> Result<Self, Error> {
+        if url.cannot_be_a_base() {
+            return Err(Error::InvalidUrl(url.to_string()));
+        }
+
+        if let Some(host) = url.host_str() {
+            if matches!(url.scheme(), "http" | "https") && !domain.matches_host(host) {
+                log::warn!("event URL {url} does not belong to site {domain}");
+            }
+        }
+
+        Ok(Self::new(domain.into(), name, url.to_string()))
+    }
+
+    /// Create a new builder, validating the site domain and URL.
+    ///
+    /// See `Self::from_url`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `domain` is not a valid `SiteDomain`, or if `url` is relative or
+    /// can't be the URL of a page.
+    pub fn try_new(domain: &str, name: String, url: &str) -> Result<Self, Error> {
+        Self::from_url(SiteDomain::new(domain)?, name, &Url::parse(url)?)
+    }
+
+    /// Create a new builder recording the event for each of the validated site `domains`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `domains` is empty.
+    pub fn from_domains(domains: &[SiteDomain], name: String, url: String) -> Result<Self, Error> {
+        if domains.is_empty() {
+            return Err(Error::InvalidSiteDomain(String::new()));
+        }
+
+        let mut builder: Self = Self::new(String::new(), name, url);
+        for domain in domains {
+            builder.add_domain(domain);
         }
+        Ok(builder)
+    }
+
+    /// Also record the event for the site `domain`, unless it already is.
+    pub fn add_domain(&mut self, domain: &SiteDomain) -> &mut Self {
+        push_domain(&mut self.domain, domain);
+        self
     }
 
     pub fn referrer(&mut self, referrer: String) -> &mut Self {
@@ -88,6 +175,11 @@ impl EventPayloadBuilder {
         self
     }
 
+    pub fn referrer_url(&mut self, referrer: &Url) -> &mut Self {
+        self.referrer = Some(referrer.to_string());
+        self
+    }
+
     pub fn screen_width(&mut self, screen_width: usize) -> &mut Self {
         self.screen_width = Some(screen_width);
         self
@@ -98,15 +190,38 @@ impl EventPayloadBuilder {
         self
     }
 
+    pub fn hash_mode(&mut self, hash_mode: bool) -> &mut Self {
+        self.hash_mode = hash_mode;
+        self
+    }
+
+    pub fn idempotency_key(&mut self, idempotency_key: String) -> &mut Self {
+        self.idempotency_key = Some(idempotency_key);
+        self
+    }
+
+    pub fn utm(&mut self, utm: Utm) -> &mut Self {
+        self.utm = Some(utm);
+        self
+    }
+
     #[must_use]
     pub fn build(&self) -> EventPayload {
-        EventPayload::new(
+        let url: String = match &self.utm {
+            Some(utm) => utm.apply(&self.url),
+            None => self.url.clone(),
+        };
+
+        let mut payload = EventPayload::new(
             self.domain.clone(),
             self.name.clone(),
-            self.url.clone(),
+            url,
             self.referrer.clone(),
             self.screen_width,
             self.props.clone(),
-        )
+        );
+        payload.hash_mode = self.hash_mode;
+        payload.idempotency_key.clone_from(&self.idempotency_key);
+        payload
     }
 }
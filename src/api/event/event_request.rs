@@ -0,0 +1,24 @@
+use crate::PrivacySignals;
+use reqwest::header::HeaderMap;
+use reqwest::{Method, Url};
+
+/// Request that would have been sent to the 'POST /api/event' API in dry-run mode.
+///
+/// See `PlausibleBuilder::dry_run`.
+#[derive(Debug, Clone)]
+pub struct EventRequest {
+    pub method: Method,
+    pub url: Url,
+    pub headers: HeaderMap,
+
+    /// The serialized JSON body.
+    pub body: String,
+
+    /// Whether the event would have been sent without the visitor's User-Agent and IP address.
+    ///
+    /// See `ConsentPolicy::Anonymize`.
+    pub anonymized: bool,
+
+    /// Privacy signals the visitor sent, which the client's `ConsentPolicy` was applied to.
+    pub privacy_signals: PrivacySignals,
+}
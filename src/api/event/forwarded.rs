@@ -0,0 +1,76 @@
+use http::HeaderMap;
+use http::header::{FORWARDED, HeaderName};
+use std::net::{IpAddr, SocketAddr};
+
+/// Header listing the addresses a request was forwarded for, client first.
+pub const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+
+/// Returns the addresses a request was forwarded for, client first.
+///
+/// The RFC 7239 `Forwarded` header is preferred, as it is standard. `X-Forwarded-For` is used if
+/// it is missing. Several instances of the same header are read in order, as if joined by commas.
+/// Hidden and unknown addresses such as `for=_hidden` or `for=unknown` are skipped.
+#[must_use]
+pub fn forwarded_chain(headers: &HeaderMap) -> Vec<IpAddr> {
+    let values = |name: &HeaderName| {
+        headers
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect::<Vec<&str>>()
+    };
+
+    let forwarded: Vec<IpAddr> = values(&FORWARDED)
+        .into_iter()
+        .flat_map(parse_forwarded)
+        .collect();
+    if !forwarded.is_empty() {
+        return forwarded;
+    }
+
+    values(&X_FORWARDED_FOR)
+        .into_iter()
+        .flat_map(parse_x_forwarded_for)
+        .collect()
+}
+
+/// Parses an `X-Forwarded-For` header, e.g. `203.0.113.7, [2001:db8::1]:443`.
+#[must_use]
+pub fn parse_x_forwarded_for(value: &str) -> Vec<IpAddr> {
+    value.split(',').filter_map(parse_node).collect()
+}
+
+/// Parses the `for` parameters of an RFC 7239 `Forwarded` header, e.g.
+/// `for=192.0.2.60;proto=http, for="[2001:db8:cafe::17]:4711"`.
+///
+/// See: <https://www.rfc-editor.org/rfc/rfc7239>
+#[must_use]
+pub fn parse_forwarded(value: &str) -> Vec<IpAddr> {
+    value
+        .split(',')
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (name, value) = pair.split_once('=')?;
+                name.trim()
+                    .eq_ignore_ascii_case("for")
+                    .then(|| parse_node(value))
+                    .flatten()
+            })
+        })
+        .collect()
+}
+
+/// Parses a node, i.e. an IPv4 or IPv6 address with an optional port, possibly quoted.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node: &str = node.trim().trim_matches('"');
+
+    // bracketed IPv6 address, with an optional port
+    if let Some(rest) = node.strip_prefix('[') {
+        let (ip, _) = rest.split_once(']')?;
+        return ip.parse().ok();
+    }
+
+    node.parse::<IpAddr>()
+        .ok()
+        .or_else(|| node.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+}
@@ -1,15 +1,47 @@
+mod bot_filter;
+mod bot_patterns;
+mod consent_policy;
+mod deduplication;
 mod event_headers;
+mod event_outcome;
 mod event_payload;
 mod event_payload_builder;
+mod event_request;
+mod forwarded;
+mod privacy_signals;
 mod prop_value;
+mod sampling;
+mod site_domain;
+mod special_events;
+mod trusted_proxies;
+mod url_filter;
+mod url_normalizer;
+mod utm;
 
+use crate::cassette::RawResponse;
 use crate::{Error, Plausible};
-use bytes::Bytes;
+pub use bot_filter::*;
+pub use bot_patterns::*;
+pub use consent_policy::*;
+pub use deduplication::*;
 pub use event_headers::*;
+pub use event_outcome::*;
 pub use event_payload::*;
 pub use event_payload_builder::*;
+pub use event_request::*;
+pub use forwarded::*;
+pub use privacy_signals::*;
 pub use prop_value::*;
-use reqwest::{RequestBuilder, StatusCode};
+use reqwest::{Request, RequestBuilder};
+pub use sampling::*;
+use site_domain::retain_domains;
+pub use site_domain::*;
+pub use special_events::*;
+use tokio::task::JoinHandle;
+pub use trusted_proxies::*;
+pub use url_filter::*;
+pub use url_normalizer::*;
+pub use utm::*;
 
 pub const PAGEVIEW_EVENT: &str = "pageview";
 
@@ -19,30 +51,180 @@ impl Plausible {
     /// When using this endpoint, it's crucial to send the HTTP headers correctly,
     /// since these are used for unique user counting.
     ///
+    /// If the client was built with `TrustedProxies`, `headers.x_forwarded_for` is first rewritten
+    /// to the single client IP address of its chain.
+    ///
+    /// If the client was built with a roll-up domain, the event is also recorded for that site.
+    ///
+    /// If the client was built with a `UrlFilter` for some of the event's domains, the event is not
+    /// recorded for those of them that don't track its page. If none of its domains are left, the
+    /// event is not sent and `EventOutcome::Excluded` is returned instead.
+    ///
+    /// If the client was built with a `BotFilter`, events from bots are not sent and
+    /// `EventOutcome::BotFiltered` is returned instead.
+    ///
+    /// If the client is in dry-run mode, the request is built and logged but not sent, and
+    /// `EventOutcome::DryRun` is returned instead.
+    ///
+    /// If Plausible received the event but ignored it, `EventOutcome::Dropped` is returned.
+    ///
+    /// If the visitor sent a Do-Not-Track or Global Privacy Control signal, the event is handled
+    /// according to the client's `ConsentPolicy`.
+    ///
+    /// If the client was built with a `SamplingPolicy`, events that are sampled out are not sent
+    /// and `EventOutcome::Sampled` is returned instead.
+    ///
+    /// If the client was built with a `UrlNormalizer`, the event's URL is normalized before it is
+    /// sent.
+    ///
+    /// If the client was built with `Deduplication`, repeats of a recently sent event are not
+    /// sent and `EventOutcome::Deduplicated` is returned instead. Events that failed to send or
+    /// were only logged in dry-run mode don't count as sent.
+    ///
     /// # Errors
     ///
     /// Will return `Err` if an error occurred while creating/sending the request,
     /// if it failed to decode the response's bytes, or if the response's status code was not a
     /// success.
     pub async fn event(
+        &self,
+        mut headers: EventHeaders,
+        mut payload: EventPayload,
+    ) -> Result<EventOutcome, Error> {
+        // keep only the client's address of the forwarding chain
+        if let Some(trusted_proxies) = &self.trusted_proxies {
+            trusted_proxies.rewrite(&mut headers);
+        }
+
+        // also record the event for the roll-up site
+        if let Some(rollup_domain) = &self.rollup_domain {
+            payload.add_domain(rollup_domain);
+        }
+
+        // only record the event for sites tracking its page, and drop it if none do
+        if payload.domains().next().is_some() {
+            retain_domains(&mut payload.domain, |domain| {
+                self.url_filters
+                    .get(domain)
+                    .is_none_or(|url_filter| url_filter.is_tracked(&payload.url))
+            });
+            if payload.domain.is_empty() {
+                return Ok(EventOutcome::Excluded);
+            }
+        }
+
+        // drop events from bots
+        if let Some(bot_filter) = &self.bot_filter {
+            if bot_filter.is_bot(&headers.user_agent) {
+                self.hooks.bot_filtered(&headers, &payload);
+                return Ok(EventOutcome::BotFiltered);
+            }
+        }
+
+        // respect visitors who opted out of tracking
+        let mut anonymize: bool = false;
+        if headers.privacy_signals.opted_out() {
+            match self.consent_policy {
+                ConsentPolicy::Ignore => {}
+                ConsentPolicy::Drop => return Ok(EventOutcome::ConsentWithheld),
+                ConsentPolicy::Anonymize => anonymize = true,
+            }
+        }
+
+        // drop events that are sampled out
+        if let Some(sampling) = &self.sampling {
+            if !sampling.sample(&headers, &mut payload) {
+                return Ok(EventOutcome::Sampled);
+            }
+        }
+
+        // strip sensitive data from the URL, and group pages by route
+        if let Some(url_normalizer) = &self.url_normalizer {
+            url_normalizer.normalize_payload(&mut payload);
+        }
+
+        // skip events sent within the deduplication window
+        let mut dedup_key: Option<String> = None;
+        if let Some(deduplication) = &self.deduplication {
+            let key: String = Deduplication::key(&headers, &payload);
+            if !deduplication.store.insert(&key, deduplication.window) {
+                return Ok(EventOutcome::Deduplicated);
+            }
+            dedup_key = Some(key);
+        }
+
+        // strip what identifies the visitor
+        if anonymize {
+            headers.user_agent = ANONYMOUS_USER_AGENT.to_string();
+            headers.x_forwarded_for.clear();
+        }
+
+        let result: Result<EventOutcome, Error> =
+            self.send_event(headers, payload, anonymize).await;
+
+        // forget events that weren't sent, so that retries and real runs are sent
+        let sent: bool = !matches!(result, Err(_) | Ok(EventOutcome::DryRun(_)));
+        if let (false, Some(deduplication), Some(key)) = (sent, &self.deduplication, dedup_key) {
+            deduplication.store.remove(&key);
+        }
+
+        result
+    }
+
+    async fn send_event(
         &self,
         headers: EventHeaders,
         payload: EventPayload,
-    ) -> Result<Bytes, Error> {
+        anonymized: bool,
+    ) -> Result<EventOutcome, Error> {
+        let privacy_signals: PrivacySignals = headers.privacy_signals;
+
         // create request
-        let request: RequestBuilder = self
+        let mut request: RequestBuilder = self
             .client
             .post(format!("{}/api/event", self.base_url))
             .header("Content-Type", "application/json")
-            .header("User-Agent", headers.user_agent)
-            .header("X-Forwarded-For", headers.x_forwarded_for);
+            .header("User-Agent", headers.user_agent);
 
-        // send request, get response
-        let response = request.json(&payload).send().await?;
+        // without X-Forwarded-For, Plausible uses the IP address of the connection
+        if !headers.x_forwarded_for.is_empty() {
+            request = request.header("X-Forwarded-For", headers.x_forwarded_for);
+        }
+
+        let request: Request = request.json(&payload).build()?;
+
+        // log the request instead of sending it
+        if self.dry_run {
+            let request: EventRequest = EventRequest {
+                method: request.method().clone(),
+                url: request.url().clone(),
+                headers: request.headers().clone(),
+                body: serde_json::to_string(&payload)?,
+                anonymized,
+                privacy_signals,
+            };
+            log::info!(
+                "dry run: {} {} {:?} {}",
+                request.method,
+                request.url,
+                request.headers,
+                request.body
+            );
+            return Ok(EventOutcome::DryRun(request));
+        }
 
-        // parse status code and returned bytes
-        let status_code: StatusCode = response.status();
-        let bytes = response.bytes().await?;
+        // send request, get response
+        let RawResponse {
+            status_code,
+            headers,
+            bytes,
+        } = self.execute(request).await?;
+        if self.debug {
+            log::info!(
+                "POST /api/event: {status_code}: {}",
+                String::from_utf8_lossy(&bytes)
+            );
+        }
 
         // check if failure
         if !status_code.is_success() {
@@ -50,6 +232,50 @@ impl Plausible {
         }
 
         // success
-        Ok(bytes)
+        let response: EventResponse = EventResponse {
+            status_code,
+            headers,
+            bytes,
+            anonymized,
+            privacy_signals,
+        };
+        if response.is_dropped() {
+            Ok(EventOutcome::Dropped(response))
+        } else {
+            Ok(EventOutcome::Accepted(response))
+        }
+    }
+
+    /// Records a pageview or custom event without waiting for it to be sent.
+    ///
+    /// The event is sent from a task spawned onto the current Tokio runtime.
+    /// Any error is passed to the handler registered with `PlausibleBuilder::on_error`.
+    ///
+    /// At most `PlausibleBuilder::max_in_flight` detached events are sent at once.
+    /// Events recorded while that many are in-flight are dropped and reported to the error
+    /// handler as `Error::InFlightLimitReached`, in which case `None` is returned.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if called from outside of a Tokio runtime.
+    pub fn event_detached(
+        &self,
+        headers: EventHeaders,
+        payload: EventPayload,
+    ) -> Option<JoinHandle<()>> {
+        let Ok(permit) = self.in_flight.clone().try_acquire_owned() else {
+            self.hooks.error(Error::InFlightLimitReached {
+                limit: self.max_in_flight,
+            });
+            return None;
+        };
+
+        let plausible: Self = self.clone();
+        Some(tokio::spawn(async move {
+            if let Err(e) = plausible.event(headers, payload).await {
+                plausible.hooks.error(e);
+            }
+            drop(permit);
+        }))
     }
 }
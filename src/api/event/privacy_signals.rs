@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// Privacy signals sent by the visitor's browser.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrivacySignals {
+    /// Whether the `DNT: 1` (Do Not Track) header was sent.
+    pub do_not_track: bool,
+
+    /// Whether the `Sec-GPC: 1` (Global Privacy Control) header was sent.
+    pub global_privacy_control: bool,
+}
+
+impl PrivacySignals {
+    #[must_use]
+    pub const fn new(do_not_track: bool, global_privacy_control: bool) -> Self {
+        Self {
+            do_not_track,
+            global_privacy_control,
+        }
+    }
+
+    /// Parse the raw values of the `DNT` and `Sec-GPC` headers, if they were sent.
+    #[must_use]
+    pub fn from_header_values(dnt: Option<&str>, sec_gpc: Option<&str>) -> Self {
+        Self {
+            do_not_track: dnt.is_some_and(|v| v.trim() == "1"),
+            global_privacy_control: sec_gpc.is_some_and(|v| v.trim() == "1"),
+        }
+    }
+
+    /// Returns whether the visitor asked not to be tracked.
+    #[must_use]
+    pub const fn opted_out(&self) -> bool {
+        self.do_not_track || self.global_privacy_control
+    }
+}
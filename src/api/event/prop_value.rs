@@ -3,7 +3,9 @@ use serde::{Deserialize, Serialize};
 /// Custom properties only accepts scalar values such as strings, numbers and booleans.
 /// Data structures such as objects, arrays etc. aren't accepted.
 // Implementation on how to constrain types easily from: https://stackoverflow.com/a/52582432/11767294
+// Untagged, so that values are sent as plain scalars rather than e.g. `{"String": "..."}`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
 pub enum PropValue {
     // string
     String(String),
@@ -26,8 +28,9 @@ pub enum PropValue {
     I128(i128),
     Isize(isize),
 
-    F32(f32),
+    // before `F32`, so that deserialized floats aren't rounded
     F64(f64),
+    F32(f32),
 }
 
 impl From<String> for PropValue {
@@ -0,0 +1,62 @@
+use crate::hash::Fnv1a;
+use crate::{EventHeaders, EventPayload, PropValue};
+use std::collections::HashMap;
+
+/// Sends only a fraction of high-volume events.
+///
+/// Whether an event is kept is decided by hashing the visitor's User-Agent and IP address,
+/// so a given visitor is either always or never counted for a sampled event.
+#[derive(Debug, Clone, Default)]
+pub struct SamplingPolicy {
+    /// Fraction of events to keep, between `0.0` and `1.0`, by event name.
+    ///
+    /// Events without a rate are always kept.
+    pub rates: HashMap<String, f64>,
+
+    /// Name of the custom property the sampling rate is attached as, if any.
+    ///
+    /// Counts in the Plausible dashboard can be scaled back up by dividing by this value.
+    pub rate_prop: Option<String>,
+}
+
+impl SamplingPolicy {
+    #[must_use]
+    pub const fn new(rates: HashMap<String, f64>) -> Self {
+        Self {
+            rates,
+            rate_prop: None,
+        }
+    }
+
+    /// Returns whether the event should be sent.
+    ///
+    /// If it should and `rate_prop` is set, the sampling rate is attached to the payload.
+    pub fn sample(&self, headers: &EventHeaders, payload: &mut EventPayload) -> bool {
+        let Some(rate) = self.rates.get(&payload.name).copied() else {
+            return true;
+        };
+
+        if !Self::keep(headers, rate) {
+            return false;
+        }
+
+        if let Some(rate_prop) = &self.rate_prop {
+            payload
+                .props
+                .get_or_insert_with(HashMap::new)
+                .insert(rate_prop.clone(), PropValue::from(rate));
+        }
+        true
+    }
+
+    /// Returns whether a visitor falls within the first `rate` of all visitors.
+    fn keep(headers: &EventHeaders, rate: f64) -> bool {
+        let mut hasher = Fnv1a::new();
+        hasher.write_field(headers.user_agent.as_bytes());
+        hasher.write_field(headers.x_forwarded_for.as_bytes());
+
+        // map the upper half of the hash onto [0, 1)
+        let bucket: u32 = u32::try_from(hasher.finish() >> 32).unwrap_or(u32::MAX);
+        f64::from(bucket) / 4_294_967_296.0 < rate
+    }
+}
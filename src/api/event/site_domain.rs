@@ -0,0 +1,127 @@
+use crate::Error;
+use std::fmt;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+/// Validated domain name of a site in Plausible.
+///
+/// This is the domain name you used when you added your site to your Plausible account, e.g.
+/// `example.com`. It doesn't need to be an actual domain name, but it must not contain a scheme,
+/// a path, whitespace or commas.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SiteDomain(String);
+
+impl SiteDomain {
+    /// Validate a site domain.
+    ///
+    /// The domain is trimmed and lowercased, as Plausible does when a site is added.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `domain` is empty, or contains a scheme (`https://`), a path (`/`),
+    /// whitespace or commas.
+    pub fn new(domain: &str) -> Result<Self, Error> {
+        let domain: String = domain.trim().to_lowercase();
+        if domain.is_empty()
+            || domain.contains("://")
+            || domain.contains(['/', ','])
+            || domain.contains(char::is_whitespace)
+        {
+            return Err(Error::InvalidSiteDomain(domain));
+        }
+        Ok(Self(domain))
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns whether a URL with the given `host` could belong to this site.
+    ///
+    /// Hosts are compared ignoring a leading `www.`, and subdomains of each other match.
+    /// Hosts and domains that don't look like internet domain names, such as `localhost`, IP
+    /// addresses or mobile app names, always match.
+    #[must_use]
+    pub fn matches_host(&self, host: &str) -> bool {
+        let host: String = host.to_lowercase();
+        let host: &str = host.strip_prefix("www.").unwrap_or(&host);
+        let domain: &str = self.0.strip_prefix("www.").unwrap_or(&self.0);
+
+        let is_domain_name = |name: &str| {
+            name.contains('.')
+                && name.parse::<std::net::IpAddr>().is_err()
+                && !name.starts_with('[')
+        };
+        if !is_domain_name(host) || !is_domain_name(domain) {
+            return true;
+        }
+
+        host == domain
+            || host.ends_with(&format!(".{domain}"))
+            || domain.ends_with(&format!(".{host}"))
+    }
+}
+
+impl fmt::Display for SiteDomain {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for SiteDomain {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for SiteDomain {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<String> for SiteDomain {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::new(&s)
+    }
+}
+
+impl From<SiteDomain> for String {
+    fn from(domain: SiteDomain) -> Self {
+        domain.0
+    }
+}
+
+/// Returns the domain names in a comma-separated list.
+pub(crate) fn split_domains(domains: &str) -> impl Iterator<Item = &str> {
+    domains
+        .split(',')
+        .map(str::trim)
+        .filter(|domain| !domain.is_empty())
+}
+
+/// Appends `domain` to a comma-separated list of domain names, unless it is already listed.
+///
+/// The list is normalized on the way, dropping empty entries and whitespace around names.
+pub(crate) fn push_domain(domains: &mut String, domain: &SiteDomain) {
+    let mut list: Vec<&str> = split_domains(domains).collect();
+    if !list.contains(&domain.as_str()) {
+        list.push(domain.as_str());
+    }
+    *domains = list.join(",");
+}
+
+/// Removes the domain names for which `keep` returns `false` from a comma-separated list.
+///
+/// The list is normalized on the way, like by `push_domain`.
+pub(crate) fn retain_domains(domains: &mut String, mut keep: impl FnMut(&str) -> bool) {
+    let list: Vec<&str> = split_domains(domains)
+        .filter(|domain| keep(domain))
+        .collect();
+    *domains = list.join(",");
+}
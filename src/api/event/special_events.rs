@@ -0,0 +1,82 @@
+use crate::{EventPayload, PropValue};
+use std::collections::HashMap;
+use url::Url;
+
+/// Name of the event recorded when a visitor clicks a link to another site.
+///
+/// See: <https://plausible.io/docs/outbound-link-click-tracking>
+pub const OUTBOUND_LINK_CLICK_EVENT: &str = "Outbound Link: Click";
+
+/// Name of the event recorded when a visitor downloads a file.
+///
+/// See: <https://plausible.io/docs/file-downloads-tracking>
+pub const FILE_DOWNLOAD_EVENT: &str = "File Download";
+
+/// Name of the event recorded when a visitor lands on a missing page.
+///
+/// See: <https://plausible.io/docs/error-pages-tracking-404>
+pub const NOT_FOUND_EVENT: &str = "404";
+
+/// Name of the event recorded when a visitor submits a form.
+///
+/// See: <https://plausible.io/docs/form-submissions-tracking>
+pub const FORM_SUBMISSION_EVENT: &str = "Form: Submission";
+
+impl EventPayload {
+    /// Create an outbound link click event, for a visitor on `page_url` clicking a link to
+    /// `target_url`.
+    ///
+    /// The target is attached as the `url` property, as shown in the Outbound Links report.
+    #[must_use]
+    pub fn outbound_link(domain: String, page_url: String, target_url: String) -> Self {
+        Self::special(
+            domain,
+            OUTBOUND_LINK_CLICK_EVENT,
+            page_url,
+            "url",
+            target_url,
+        )
+    }
+
+    /// Create a file download event, for a visitor on `page_url` downloading `file_url`.
+    ///
+    /// The file is attached as the `url` property, as shown in the File Downloads report.
+    #[must_use]
+    pub fn file_download(domain: String, page_url: String, file_url: String) -> Self {
+        Self::special(domain, FILE_DOWNLOAD_EVENT, page_url, "url", file_url)
+    }
+
+    /// Create a 404 event, for a visitor landing on the missing page `page_url`.
+    ///
+    /// The page's path is attached as the `path` property, as shown in the 404 report.
+    #[must_use]
+    pub fn not_found(domain: String, page_url: String) -> Self {
+        let path: String =
+            Url::parse(&page_url).map_or_else(|_| page_url.clone(), |url| url.path().to_string());
+        Self::special(domain, NOT_FOUND_EVENT, page_url, "path", path)
+    }
+
+    /// Create a form submission event, for a visitor submitting a form on `page_url`.
+    #[must_use]
+    pub fn form_submission(domain: String, page_url: String) -> Self {
+        Self::new(
+            domain,
+            FORM_SUBMISSION_EVENT.to_string(),
+            page_url,
+            None,
+            None,
+            None,
+        )
+    }
+
+    fn special(domain: String, name: &str, page_url: String, prop: &str, value: String) -> Self {
+        Self::new(
+            domain,
+            name.to_string(),
+            page_url,
+            None,
+            None,
+            Some(HashMap::from([(prop.to_string(), PropValue::from(value))])),
+        )
+    }
+}
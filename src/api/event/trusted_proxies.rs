@@ -0,0 +1,228 @@
+use crate::{Error, EventHeaders, forwarded_chain, parse_x_forwarded_for};
+use http::HeaderMap;
+use http::header::HeaderName;
+use ipnet::IpNet;
+use std::net::IpAddr;
+
+/// Loopback, private and unique local networks, where load balancers and reverse proxies usually
+/// run.
+pub const PRIVATE_NETWORKS: &[&str] = &[
+    "127.0.0.0/8",
+    "10.0.0.0/8",
+    "172.16.0.0/12",
+    "192.168.0.0/16",
+    "::1/128",
+    "fc00::/7",
+];
+
+/// Header set by Cloudflare to the client IP address.
+pub const CF_CONNECTING_IP: HeaderName = HeaderName::from_static("cf-connecting-ip");
+
+/// Header set by nginx's `real_ip` module, and many other reverse proxies, to the client IP
+/// address.
+pub const X_REAL_IP: HeaderName = HeaderName::from_static("x-real-ip");
+
+/// Header set by Akamai and Cloudflare Enterprise to the client IP address.
+pub const TRUE_CLIENT_IP: HeaderName = HeaderName::from_static("true-client-ip");
+
+/// Header set by Fly.io to the client IP address.
+pub const FLY_CLIENT_IP: HeaderName = HeaderName::from_static("fly-client-ip");
+
+/// Header set by Fastly to the client IP address.
+pub const FASTLY_CLIENT_IP: HeaderName = HeaderName::from_static("fastly-client-ip");
+
+/// Decides which address of a forwarding chain is the client's.
+///
+/// Anyone can send a `Forwarded` or `X-Forwarded-For` header, so only the addresses appended by
+/// proxies in front of the application can be relied on. The chain is read from right to left,
+/// the connection's peer address being its last hop, and the first address that isn't a trusted
+/// proxy is the client's. A proxy is trusted if it is in one of `cidrs`, or if it is one of the
+/// last `hops` addresses. If every address is trusted, the left-most one is the client's.
+///
+/// With the default policy nothing is trusted, so the peer address is the client's.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    /// Networks of trusted proxies.
+    pub cidrs: Vec<IpNet>,
+
+    /// Number of proxies in front of the application, counting the peer.
+    pub hops: usize,
+
+    /// Header a CDN or reverse proxy sets to the client IP address, read instead of the
+    /// forwarding chain, if set.
+    ///
+    /// **Clients can send this header themselves.** It is only read from requests whose peer is
+    /// in one of `cidrs`, so those must be the networks of the CDN or proxy setting it; with no
+    /// `cidrs`, it is never read.
+    pub client_ip_header: Option<HeaderName>,
+}
+
+impl TrustedProxies {
+    /// Create a new policy that trusts no proxy.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new policy that trusts proxies in the given networks, e.g. `10.0.0.0/8`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if one of `cidrs` isn't a valid network.
+    pub fn with_cidrs<S: AsRef<str>>(cidrs: &[S]) -> Result<Self, Error> {
+        let mut trusted_proxies: Self = Self::new();
+        for cidr in cidrs {
+            let cidr: &str = cidr.as_ref();
+            trusted_proxies.trust(
+                cidr.parse()
+                    .map_err(|_| Error::InvalidCidr(cidr.to_string()))?,
+            );
+        }
+        Ok(trusted_proxies)
+    }
+
+    /// Create a new policy that reads the client IP address from `header`, when sent by a proxy
+    /// in the given networks.
+    ///
+    /// **Only pass the networks of the proxy that sets `header`**: anyone else can set it to any
+    /// address, see `Self::client_ip_header`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if one of `cidrs` isn't a valid network.
+    pub fn with_client_ip_header<S: AsRef<str>>(
+        header: HeaderName,
+        cidrs: &[S],
+    ) -> Result<Self, Error> {
+        let mut trusted_proxies: Self = Self::with_cidrs(cidrs)?;
+        trusted_proxies.client_ip_header(header);
+        Ok(trusted_proxies)
+    }
+
+    /// Read the client IP address from Cloudflare's `CF-Connecting-IP` header, when sent from
+    /// Cloudflare's networks.
+    ///
+    /// Cloudflare publishes them at <https://www.cloudflare.com/ips/>.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if one of `cidrs` isn't a valid network.
+    pub fn cloudflare<S: AsRef<str>>(cidrs: &[S]) -> Result<Self, Error> {
+        Self::with_client_ip_header(CF_CONNECTING_IP, cidrs)
+    }
+
+    /// Read the client IP address from the `X-Real-IP` header, when sent by a reverse proxy in
+    /// the given networks, e.g. `PRIVATE_NETWORKS`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if one of `cidrs` isn't a valid network.
+    pub fn x_real_ip<S: AsRef<str>>(cidrs: &[S]) -> Result<Self, Error> {
+        Self::with_client_ip_header(X_REAL_IP, cidrs)
+    }
+
+    /// Read the client IP address from the `True-Client-IP` header, when sent from the CDN's
+    /// networks.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if one of `cidrs` isn't a valid network.
+    pub fn true_client_ip<S: AsRef<str>>(cidrs: &[S]) -> Result<Self, Error> {
+        Self::with_client_ip_header(TRUE_CLIENT_IP, cidrs)
+    }
+
+    /// Read the client IP address from Fly.io's `Fly-Client-IP` header, when sent by Fly.io's
+    /// proxy, usually from `PRIVATE_NETWORKS`.
+    ///
+    /// See: <https://fly.io/docs/networking/request-headers/>
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if one of `cidrs` isn't a valid network.
+    pub fn fly<S: AsRef<str>>(cidrs: &[S]) -> Result<Self, Error> {
+        Self::with_client_ip_header(FLY_CLIENT_IP, cidrs)
+    }
+
+    /// Read the client IP address from Fastly's `Fastly-Client-IP` header, when sent from
+    /// Fastly's networks.
+    ///
+    /// Fastly publishes them at <https://api.fastly.com/public-ip-list>.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if one of `cidrs` isn't a valid network.
+    pub fn fastly<S: AsRef<str>>(cidrs: &[S]) -> Result<Self, Error> {
+        Self::with_client_ip_header(FASTLY_CLIENT_IP, cidrs)
+    }
+
+    /// Trust proxies in `cidr`.
+    pub fn trust(&mut self, cidr: IpNet) -> &mut Self {
+        self.cidrs.push(cidr);
+        self
+    }
+
+    pub fn hops(&mut self, hops: usize) -> &mut Self {
+        self.hops = hops;
+        self
+    }
+
+    /// Read the client IP address from `client_ip_header`, when sent by a trusted proxy.
+    pub fn client_ip_header(&mut self, client_ip_header: HeaderName) -> &mut Self {
+        self.client_ip_header = Some(client_ip_header);
+        self
+    }
+
+    /// Returns whether `ip` is in one of the trusted networks.
+    #[must_use]
+    pub fn is_trusted(&self, ip: &IpAddr) -> bool {
+        self.cidrs.iter().any(|cidr| cidr.contains(ip))
+    }
+
+    /// Returns the client IP address of an incoming request.
+    ///
+    /// `peer` is the address of the connection, if known.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if no client IP address can be determined.
+    pub fn client_ip(&self, headers: &HeaderMap, peer: Option<IpAddr>) -> Result<IpAddr, Error> {
+        if let Some(name) = &self.client_ip_header {
+            // only proxies can be trusted to set it, and without `cidrs` none are
+            let peer_trusted: bool = peer.is_some_and(|peer| self.is_trusted(&peer));
+            let client_ip: Option<IpAddr> = headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| parse_x_forwarded_for(value).first().copied());
+            if let (true, Some(client_ip)) = (peer_trusted, client_ip) {
+                return Ok(client_ip);
+            }
+        }
+
+        let mut chain: Vec<IpAddr> = forwarded_chain(headers);
+        chain.extend(peer);
+        self.pick(&chain).ok_or(Error::MissingClientIp)
+    }
+
+    /// Returns the client's address in `chain`, ordered client first.
+    #[must_use]
+    pub fn pick(&self, chain: &[IpAddr]) -> Option<IpAddr> {
+        chain
+            .iter()
+            .rev()
+            .enumerate()
+            .find(|(hop, ip)| *hop >= self.hops && !self.is_trusted(ip))
+            .map(|(_, ip)| *ip)
+            .or_else(|| chain.first().copied())
+    }
+
+    /// Rewrites `headers.x_forwarded_for` to the single client IP address of its chain.
+    ///
+    /// Useful when the headers were built by hand from an incoming `X-Forwarded-For` header. As the
+    /// peer address isn't part of it, `hops` counts only the proxies that appended to the header.
+    /// It is left as is if it contains no valid address.
+    pub fn rewrite(&self, headers: &mut EventHeaders) {
+        if let Some(client_ip) = self.pick(&parse_x_forwarded_for(&headers.x_forwarded_for)) {
+            headers.x_forwarded_for = client_ip.to_string();
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use url::Url;
+
+/// Decides which pages are tracked by matching their path against wildcard patterns, like the
+/// `data-include` and `data-exclude` attributes of the Plausible tracker script.
+///
+/// `*` matches any characters within a single path segment, and `**` matches any characters
+/// across segments. A trailing slash in the page's path is ignored.
+/// For example, `/blog/*` matches `/blog/hello` but not `/blog/hello/comments`, and
+/// `/admin/**` matches every page below `/admin`.
+///
+/// See: <https://plausible.io/docs/excluding-pages>
+#[derive(Debug, Clone, Default)]
+pub struct UrlFilter {
+    /// Patterns of the pages to track.
+    ///
+    /// If empty, all pages are tracked unless excluded.
+    pub include: Vec<String>,
+
+    /// Patterns of the pages not to track.
+    pub exclude: Vec<String>,
+}
+
+impl UrlFilter {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+
+    /// Track pages whose path matches `pattern`.
+    pub fn include(&mut self, pattern: &str) -> &mut Self {
+        self.include.push(pattern.trim().to_string());
+        self
+    }
+
+    /// Don't track pages whose path matches `pattern`.
+    pub fn exclude(&mut self, pattern: &str) -> &mut Self {
+        self.exclude.push(pattern.trim().to_string());
+        self
+    }
+
+    /// Returns whether the page at `url` should be tracked.
+    ///
+    /// URLs that can't be parsed are always tracked.
+    #[must_use]
+    pub fn is_tracked(&self, url: &str) -> bool {
+        let Ok(url) = Url::parse(url) else {
+            return true;
+        };
+        self.is_path_tracked(url.path())
+    }
+
+    /// Returns whether the page at `path` should be tracked.
+    #[must_use]
+    pub fn is_path_tracked(&self, path: &str) -> bool {
+        let included: bool = self.include.is_empty()
+            || self
+                .include
+                .iter()
+                .any(|p| matches(p.as_bytes(), path.as_bytes()));
+        included
+            && !self
+                .exclude
+                .iter()
+                .any(|p| matches(p.as_bytes(), path.as_bytes()))
+    }
+}
+
+/// Returns whether `path` matches the wildcard `pattern`.
+fn matches(pattern: &[u8], path: &[u8]) -> bool {
+    match pattern {
+        // a trailing slash is optional
+        [] => path.is_empty() || path == b"/",
+
+        // `**` matches anything
+        [b'*', b'*', rest @ ..] => (0..=path.len()).any(|i| matches(rest, &path[i..])),
+
+        // `*` matches anything up to the end of the path segment
+        [b'*', rest @ ..] => {
+            let segment_len: usize = path
+                .iter()
+                .position(|c| *c == b'/' || c.is_ascii_whitespace())
+                .unwrap_or(path.len());
+            (0..=segment_len).any(|i| matches(rest, &path[i..]))
+        }
+
+        [c, rest @ ..] => path.first() == Some(c) && matches(rest, &path[1..]),
+    }
+}
@@ -0,0 +1,232 @@
+use crate::EventPayload;
+use url::{Url, form_urlencoded};
+
+/// Query parameters Plausible extracts traffic sources from.
+///
+/// See: <https://plausible.io/docs/manual-link-tagging>
+pub const DEFAULT_QUERY_PARAMS: &[&str] = &[
+    "utm_source",
+    "utm_medium",
+    "utm_campaign",
+    "utm_content",
+    "utm_term",
+    "ref",
+    "source",
+];
+
+/// What to do with a trailing slash at the end of a URL's path.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrailingSlash {
+    /// Leave the path as is.
+    #[default]
+    Preserve,
+
+    /// Remove the trailing slash, e.g. `/blog/` becomes `/blog`.
+    Remove,
+
+    /// Add a trailing slash, e.g. `/blog` becomes `/blog/`.
+    Add,
+}
+
+/// Rewrites `EventPayload::url` before it is sent, to keep sensitive data out of Plausible and
+/// to group pages that only differ by an ID into one row of the dashboard.
+///
+/// URLs that can't be parsed are sent as is.
+#[derive(Debug, Clone)]
+pub struct UrlNormalizer {
+    /// Query parameters that are kept, all others are removed.
+    ///
+    /// If `None`, all query parameters are kept. Defaults to `DEFAULT_QUERY_PARAMS`.
+    pub allowed_query_params: Option<Vec<String>>,
+
+    /// Route templates that path segments are rewritten to, e.g. `/users/:id/settings`.
+    ///
+    /// A segment starting with `:` matches any segment, which is replaced by the placeholder.
+    /// So `/users/8123/settings` becomes `/users/:id/settings`.
+    /// The first matching template is used.
+    pub routes: Vec<String>,
+
+    /// Whether the path is lowercased.
+    pub lowercase: bool,
+
+    /// What to do with a trailing slash at the end of the path.
+    pub trailing_slash: TrailingSlash,
+}
+
+impl UrlNormalizer {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            allowed_query_params: Some(
+                DEFAULT_QUERY_PARAMS
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect(),
+            ),
+            routes: Vec::new(),
+            lowercase: false,
+            trailing_slash: TrailingSlash::Preserve,
+        }
+    }
+
+    /// Keep the query parameter `name`, in addition to the ones already allowed.
+    pub fn allow_query_param(&mut self, name: &str) -> &mut Self {
+        self.allowed_query_params
+            .get_or_insert_with(Vec::new)
+            .push(name.to_string());
+        self
+    }
+
+    /// Keep all query parameters.
+    pub fn allow_all_query_params(&mut self) -> &mut Self {
+        self.allowed_query_params = None;
+        self
+    }
+
+    /// Rewrite paths matching the route `template`, e.g. `/users/:id/settings`.
+    pub fn route(&mut self, template: &str) -> &mut Self {
+        self.routes.push(template.to_string());
+        self
+    }
+
+    pub fn lowercase(&mut self, lowercase: bool) -> &mut Self {
+        self.lowercase = lowercase;
+        self
+    }
+
+    pub fn trailing_slash(&mut self, trailing_slash: TrailingSlash) -> &mut Self {
+        self.trailing_slash = trailing_slash;
+        self
+    }
+
+    /// Returns the normalized `url`.
+    ///
+    /// The fragment is removed, as Plausible ignores it.
+    #[must_use]
+    pub fn normalize(&self, url: &str) -> String {
+        self.normalize_url(url, false)
+    }
+
+    /// Normalizes `EventPayload::url`.
+    ///
+    /// If `EventPayload::hash_mode` is set, the fragment is kept and normalized like a path, e.g.
+    /// `https://example.com/#/users/8123` becomes `https://example.com/#/users/:id`.
+    pub fn normalize_payload(&self, payload: &mut EventPayload) {
+        payload.url = self.normalize_url(&payload.url, payload.hash_mode);
+    }
+
+    fn normalize_url(&self, url: &str, hash_mode: bool) -> String {
+        let Ok(mut url) = Url::parse(url) else {
+            return url.to_string();
+        };
+
+        // fragments are ignored by Plausible, unless they are routes in hash mode
+        let fragment: Option<String> = url
+            .fragment()
+            .filter(|_| hash_mode)
+            .map(|fragment| self.normalize_route(fragment));
+        url.set_fragment(None);
+
+        // remove query parameters that aren't allowed
+        let query: Option<String> = url.query().and_then(|query| self.filter_query(query));
+        url.set_query(query.as_deref());
+
+        // URLs such as `mailto:` have no path to normalize
+        if !url.cannot_be_a_base() {
+            let path: String = self.normalize_path(url.path());
+            url.set_path(&path);
+        }
+
+        url.set_fragment(fragment.as_deref());
+        url.to_string()
+    }
+
+    /// Normalizes a hash-based route such as `/users/8123?tab=billing`.
+    fn normalize_route(&self, route: &str) -> String {
+        if !route.starts_with('/') {
+            return route.to_string();
+        }
+
+        let (path, query) = route.split_once('?').unwrap_or((route, ""));
+        let path: String = self.normalize_path(path);
+        match self.filter_query(query) {
+            Some(query) => format!("{path}?{query}"),
+            None => path,
+        }
+    }
+
+    fn normalize_path(&self, path: &str) -> String {
+        let mut path: String = if self.lowercase {
+            path.to_lowercase()
+        } else {
+            path.to_string()
+        };
+
+        if let Some(templated) = self.routes.iter().find_map(|t| apply_route(t, &path)) {
+            path = templated;
+        }
+
+        match self.trailing_slash {
+            TrailingSlash::Preserve => {}
+            TrailingSlash::Remove => {
+                while path.len() > 1 && path.ends_with('/') {
+                    path.pop();
+                }
+            }
+            TrailingSlash::Add => {
+                if !path.ends_with('/') {
+                    path.push('/');
+                }
+            }
+        }
+        path
+    }
+
+    /// Returns `query` with only the allowed parameters, or `None` if none are left.
+    fn filter_query(&self, query: &str) -> Option<String> {
+        let Some(allowed_query_params) = &self.allowed_query_params else {
+            return Some(query.to_string()).filter(|query| !query.is_empty());
+        };
+
+        let mut serializer = form_urlencoded::Serializer::new(String::new());
+        let mut empty: bool = true;
+        for (name, value) in form_urlencoded::parse(query.as_bytes()) {
+            if allowed_query_params.iter().any(|p| *p == name) {
+                serializer.append_pair(&name, &value);
+                empty = false;
+            }
+        }
+        (!empty).then(|| serializer.finish())
+    }
+}
+
+impl Default for UrlNormalizer {
+    /// Defaults to `Self::new()`.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns `path` rewritten to the route `template`, if it matches.
+fn apply_route(template: &str, path: &str) -> Option<String> {
+    let trailing_slash: bool = path.len() > 1 && path.ends_with('/');
+    let template_segments: Vec<&str> = template.trim_matches('/').split('/').collect();
+    let path_segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    if template_segments.len() != path_segments.len() {
+        return None;
+    }
+
+    let matched: bool = template_segments
+        .iter()
+        .zip(&path_segments)
+        .all(|(t, p)| t.starts_with(':') || t == p);
+    if !matched {
+        return None;
+    }
+
+    let mut templated: String = format!("/{}", template_segments.join("/"));
+    if trailing_slash {
+        templated.push('/');
+    }
+    Some(templated)
+}
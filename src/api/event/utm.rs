@@ -0,0 +1,149 @@
+use crate::Error;
+use url::Url;
+
+/// UTM parameters, which Plausible extracts from `EventPayload::url` to attribute traffic
+/// sources.
+///
+/// See: <https://plausible.io/docs/manual-link-tagging>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Utm {
+    /// `utm_source`, e.g. `newsletter`.
+    ///
+    /// When parsing, the `source` and `ref` aliases recognized by Plausible are used if
+    /// `utm_source` is missing.
+    pub source: Option<String>,
+
+    /// `utm_medium`, e.g. `email`.
+    pub medium: Option<String>,
+
+    /// `utm_campaign`, e.g. `spring_sale`.
+    pub campaign: Option<String>,
+
+    /// `utm_content`, e.g. `header_link`.
+    pub content: Option<String>,
+
+    /// `utm_term`, e.g. `running+shoes`.
+    pub term: Option<String>,
+}
+
+impl Utm {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            source: None,
+            medium: None,
+            campaign: None,
+            content: None,
+            term: None,
+        }
+    }
+
+    /// Parse the UTM parameters of a URL.
+    ///
+    /// Relative URLs such as `/landing?utm_source=newsletter` are accepted too, as found in the
+    /// request line of an incoming HTTP request.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `url` is not a valid URL.
+    pub fn from_url(url: &str) -> Result<Self, Error> {
+        let base: Url = Url::parse("http://localhost/")?;
+        let url: Url = Url::options().base_url(Some(&base)).parse(url)?;
+
+        let mut utm: Self = Self::new();
+        let (mut source, mut ref_) = (None, None);
+        for (name, value) in url.query_pairs() {
+            let value: Option<String> = Some(value.into_owned());
+            match name.as_ref() {
+                "utm_source" => utm.source = value,
+                "utm_medium" => utm.medium = value,
+                "utm_campaign" => utm.campaign = value,
+                "utm_content" => utm.content = value,
+                "utm_term" => utm.term = value,
+                "source" => source = value,
+                "ref" => ref_ = value,
+                _ => {}
+            }
+        }
+
+        // `utm_source` takes precedence over its aliases
+        utm.source = utm.source.or(source).or(ref_);
+        Ok(utm)
+    }
+
+    pub fn source(&mut self, source: String) -> &mut Self {
+        self.source = Some(source);
+        self
+    }
+
+    pub fn medium(&mut self, medium: String) -> &mut Self {
+        self.medium = Some(medium);
+        self
+    }
+
+    pub fn campaign(&mut self, campaign: String) -> &mut Self {
+        self.campaign = Some(campaign);
+        self
+    }
+
+    pub fn content(&mut self, content: String) -> &mut Self {
+        self.content = Some(content);
+        self
+    }
+
+    pub fn term(&mut self, term: String) -> &mut Self {
+        self.term = Some(term);
+        self
+    }
+
+    /// Returns whether no UTM parameter is set.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.source.is_none()
+            && self.medium.is_none()
+            && self.campaign.is_none()
+            && self.content.is_none()
+            && self.term.is_none()
+    }
+
+    /// Returns `url` with these UTM parameters encoded into its query.
+    ///
+    /// Parameters that are set replace any existing value in `url`, including the `source` and
+    /// `ref` aliases of `utm_source`. URLs that can't be parsed are returned as is.
+    #[must_use]
+    pub fn apply(&self, url: &str) -> String {
+        let Ok(mut url) = Url::parse(url) else {
+            return url.to_string();
+        };
+
+        let params: [(&str, &Option<String>); 5] = [
+            ("utm_source", &self.source),
+            ("utm_medium", &self.medium),
+            ("utm_campaign", &self.campaign),
+            ("utm_content", &self.content),
+            ("utm_term", &self.term),
+        ];
+        let replaced = |name: &str| -> bool {
+            params.iter().any(|(n, v)| v.is_some() && *n == name)
+                || (self.source.is_some() && (name == "source" || name == "ref"))
+        };
+
+        let mut query: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(name, _)| !replaced(name))
+            .map(|(name, value)| (name.into_owned(), value.into_owned()))
+            .collect();
+        for (name, value) in params {
+            if let Some(value) = value {
+                query.push((name.to_string(), value.clone()));
+            }
+        }
+
+        if query.is_empty() {
+            url.set_query(None);
+        } else {
+            url.query_pairs_mut().clear().extend_pairs(query);
+        }
+        url.to_string()
+    }
+}
@@ -1,5 +1,6 @@
+use crate::cassette::RawResponse;
 use crate::{Error, Plausible};
-use reqwest::{RequestBuilder, StatusCode};
+use reqwest::Request;
 use serde::{Deserialize, Serialize};
 
 impl Plausible {
@@ -12,14 +13,21 @@ impl Plausible {
     /// success, or if it failed to serialize the response bytes into `HealthResponse`.
     pub async fn health(&self) -> Result<HealthResponse, Error> {
         // create request
-        let request: RequestBuilder = self.client.get(format!("{}/api/health", self.base_url));
+        let request: Request = self
+            .client
+            .get(format!("{}/api/health", self.base_url))
+            .build()?;
 
         // send request, get response
-        let response = request.send().await?;
-
-        // parse status code and returned bytes
-        let status_code: StatusCode = response.status();
-        let bytes = response.bytes().await?;
+        let RawResponse {
+            status_code, bytes, ..
+        } = self.execute(request).await?;
+        if self.debug {
+            log::info!(
+                "GET /api/health: {status_code}: {}",
+                String::from_utf8_lossy(&bytes)
+            );
+        }
 
         // check if failure
         if !status_code.is_success() {
@@ -0,0 +1,36 @@
+//! Local stand-in for the Plausible Analytics API, printing every event it receives to stdout.
+//!
+//! `cargo run --features test-util --bin plausible-local -- [ADDRESS]`
+//!
+//! Listens on `127.0.0.1:8000` unless another address is given.
+//! Point a client at it with `PlausibleBuilder::base_url`.
+
+use plausible_rs::StdoutSink;
+use plausible_rs::test_util::PlausibleServer;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+const DEFAULT_ADDR: &str = "127.0.0.1:8000";
+
+#[tokio::main]
+async fn main() {
+    let addr: SocketAddr = std::env::args()
+        .nth(1)
+        .as_deref()
+        .unwrap_or(DEFAULT_ADDR)
+        .parse()
+        .expect("address should be an IP address and port, e.g. `127.0.0.1:8000`");
+
+    let server: PlausibleServer = PlausibleServer::builder()
+        .addr(addr)
+        .sink(Arc::new(StdoutSink::new()))
+        .start()
+        .await
+        .expect("failed to start local Plausible");
+    eprintln!("local Plausible listening on {}", server.base_url());
+
+    tokio::select! {
+        () = server.wait() => {}
+        _ = tokio::signal::ctrl_c() => {}
+    }
+}
@@ -0,0 +1,289 @@
+use crate::Error;
+use bytes::Bytes;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Client, Request, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Replaces redacted header values and IP addresses in cassettes.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Headers whose values are always redacted, as they carry credentials or visitors' IP addresses.
+pub const REDACTED_HEADERS: &[&str] = &[
+    "authorization",
+    "cookie",
+    "forwarded",
+    "proxy-authorization",
+    "set-cookie",
+    "x-forwarded-for",
+    "x-real-ip",
+];
+
+/// Whether a `Cassette` records or replays requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Send requests, and write every request and response to the cassette file.
+    ///
+    /// The file is overwritten.
+    Record,
+
+    /// Don't send requests, but answer them from the cassette file.
+    Replay,
+}
+
+/// File of recorded requests and responses, to test code that calls Plausible offline.
+///
+/// In record mode, requests are sent and each request and response pair is written to the
+/// file, with the values of `REDACTED_HEADERS` and any IP address replaced by `REDACTED`.
+/// In replay mode, requests are answered from the file instead of being sent. A request is
+/// matched by its method, URL and body, after redaction. Each recorded response is replayed once
+/// before being reused. Requests that match nothing fail with `Error::UnmatchedRequest`.
+///
+/// See `PlausibleBuilder::cassette`.
+#[derive(Debug, Clone)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    state: Arc<Mutex<CassetteState>>,
+}
+
+#[derive(Debug, Default)]
+struct CassetteState {
+    /// Loaded from the file on the first replayed request.
+    interactions: Option<Vec<Interaction>>,
+    used: Vec<bool>,
+}
+
+/// Contents of a cassette file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CassetteFile {
+    pub interactions: Vec<Interaction>,
+}
+
+/// A request and the response Plausible sent to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+/// Status code, headers and body of a response, whether received or replayed.
+pub(crate) struct RawResponse {
+    pub(crate) status_code: StatusCode,
+    pub(crate) headers: HeaderMap,
+    pub(crate) bytes: Bytes,
+}
+
+impl Cassette {
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>, mode: CassetteMode) -> Self {
+        Self {
+            path: path.into(),
+            mode,
+            state: Arc::new(Mutex::new(CassetteState::default())),
+        }
+    }
+
+    /// Record requests to the cassette file at `path`.
+    #[must_use]
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self::new(path, CassetteMode::Record)
+    }
+
+    /// Replay requests from the cassette file at `path`.
+    #[must_use]
+    pub fn replay(path: impl Into<PathBuf>) -> Self {
+        Self::new(path, CassetteMode::Replay)
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    #[must_use]
+    pub const fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// Sends or replays `request`, depending on the mode.
+    pub(crate) async fn execute(
+        &self,
+        client: &Client,
+        request: Request,
+    ) -> Result<RawResponse, Error> {
+        let recorded: RecordedRequest = RecordedRequest::from_request(&request);
+        match self.mode {
+            CassetteMode::Record => {
+                let response: RawResponse = send(client, request).await?;
+                self.record_interaction(recorded, &response).await?;
+                Ok(response)
+            }
+            CassetteMode::Replay => self.replay_interaction(&recorded).await,
+        }
+    }
+
+    async fn record_interaction(
+        &self,
+        request: RecordedRequest,
+        response: &RawResponse,
+    ) -> Result<(), Error> {
+        let mut state = self.state.lock().await;
+        let interactions: &mut Vec<Interaction> = state.interactions.get_or_insert_with(Vec::new);
+        interactions.push(Interaction {
+            request,
+            response: RecordedResponse {
+                status: response.status_code.as_u16(),
+                headers: redact_headers(&response.headers),
+                body: redact_ips(&String::from_utf8_lossy(&response.bytes)),
+            },
+        });
+
+        // rewrite the whole file, so that it is valid even if recording stops early
+        let file: CassetteFile = CassetteFile {
+            interactions: interactions.clone(),
+        };
+        tokio::fs::write(&self.path, serde_json::to_vec_pretty(&file)?).await?;
+        Ok(())
+    }
+
+    async fn replay_interaction(&self, request: &RecordedRequest) -> Result<RawResponse, Error> {
+        let mut state = self.state.lock().await;
+        if state.interactions.is_none() {
+            let file: CassetteFile = serde_json::from_slice(&tokio::fs::read(&self.path).await?)?;
+            state.used = vec![false; file.interactions.len()];
+            state.interactions = Some(file.interactions);
+        }
+        let CassetteState { interactions, used } = &mut *state;
+        let interactions: &[Interaction] = interactions.as_deref().unwrap_or_default();
+
+        let matches = |i: &usize| interactions[*i].request.matches(request);
+        let unused: Option<usize> = (0..interactions.len()).find(|i| !used[*i] && matches(i));
+        let Some(index) = unused.or_else(|| (0..interactions.len()).find(matches)) else {
+            let message: String = format!(
+                "no interaction in cassette {} matches {} {} {}",
+                self.path.display(),
+                request.method,
+                request.url,
+                request.body
+            );
+            log::error!("{message}");
+            return Err(Error::UnmatchedRequest(message));
+        };
+        used[index] = true;
+
+        let response: &RecordedResponse = &interactions[index].response;
+        let mut headers: HeaderMap = HeaderMap::new();
+        for (name, value) in &response.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.insert(name, value);
+            }
+        }
+        Ok(RawResponse {
+            status_code: StatusCode::from_u16(response.status)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            headers,
+            bytes: Bytes::from(response.body.clone()),
+        })
+    }
+}
+
+impl RecordedRequest {
+    fn from_request(request: &Request) -> Self {
+        let body: String = request
+            .body()
+            .and_then(reqwest::Body::as_bytes)
+            .map(String::from_utf8_lossy)
+            .unwrap_or_default()
+            .into_owned();
+        Self {
+            method: request.method().to_string(),
+            url: redact_ips(request.url().as_str()),
+            headers: redact_headers(request.headers()),
+            body: redact_ips(&body),
+        }
+    }
+
+    fn matches(&self, other: &Self) -> bool {
+        self.method == other.method && self.url == other.url && self.body == other.body
+    }
+}
+
+/// Sends `request` with `client`, reading the whole response.
+pub(crate) async fn send(client: &Client, request: Request) -> Result<RawResponse, Error> {
+    let response = client.execute(request).await?;
+    Ok(RawResponse {
+        status_code: response.status(),
+        headers: response.headers().clone(),
+        bytes: response.bytes().await?,
+    })
+}
+
+fn redact_headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value: String = if REDACTED_HEADERS.contains(&name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                redact_ips(&String::from_utf8_lossy(value.as_bytes()))
+            };
+            (name.to_string(), value)
+        })
+        .collect()
+}
+
+/// Replaces IPv4 and IPv6 addresses, with or without a port, by `REDACTED`.
+///
+/// Product versions that look like IPv4 addresses, such as the `105.0.0.0` of `Chrome/105.0.0.0`,
+/// are kept.
+fn redact_ips(text: &str) -> String {
+    let is_ip_char = |c: char| c.is_ascii_hexdigit() || c == '.' || c == ':';
+    let is_ip =
+        |token: &str| token.parse::<IpAddr>().is_ok() || token.parse::<SocketAddr>().is_ok();
+
+    let mut redacted: String = String::with_capacity(text.len());
+    let mut rest: &str = text;
+    while let Some(start) = rest.find(is_ip_char) {
+        redacted.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end: usize = rest.find(|c: char| !is_ip_char(c)).unwrap_or(rest.len());
+        let token: &str = &rest[..end];
+
+        // IPv4 addresses are often followed by a period ending a sentence
+        let trimmed: &str = token.trim_end_matches(['.', ':']);
+        // versions, e.g. `Chrome/105.0.0.0`, follow a slash, while hosts of URLs follow two
+        let is_version: bool = redacted.ends_with('/') && !redacted.ends_with("//");
+        if !is_version && is_ip(trimmed) {
+            redacted.push_str(REDACTED);
+            redacted.push_str(&token[trimmed.len()..]);
+        } else {
+            redacted.push_str(token);
+        }
+        rest = &rest[end..];
+    }
+    redacted.push_str(rest);
+    redacted
+}
@@ -16,6 +16,31 @@ pub enum Error {
 
     /// Error occurred while using the `serde` library.
     SerdeError(serde_json::Error),
+
+    /// Error occurred while reading or writing a file.
+    IoError(std::io::Error),
+
+    /// Error occurred while using the `url` library.
+    UrlError(url::ParseError),
+
+    /// A site domain was empty, or contained a scheme, a path, whitespace or commas.
+    InvalidSiteDomain(String),
+
+    /// A URL can't be used for an event, e.g. because it is relative.
+    InvalidUrl(String),
+
+    /// No client IP address was found in a request's `Forwarded` or `X-Forwarded-For` headers,
+    /// and its peer address is unknown.
+    MissingClientIp,
+
+    /// A trusted proxy network wasn't a valid CIDR, e.g. `10.0.0.0/8`.
+    InvalidCidr(String),
+
+    /// A request matched no interaction of the cassette being replayed.
+    UnmatchedRequest(String),
+
+    /// A detached event was dropped because too many events were already in-flight.
+    InFlightLimitReached { limit: usize },
 }
 
 impl error::Error for Error {}
@@ -29,6 +54,22 @@ impl fmt::Display for Error {
                 write!(f, "{status_code}: {text}")
             }
             Self::SerdeError(e) => write!(f, "{e}"),
+            Self::IoError(e) => write!(f, "{e}"),
+            Self::UrlError(e) => write!(f, "{e}"),
+            Self::InvalidSiteDomain(domain) => write!(f, "invalid site domain: {domain:?}"),
+            Self::InvalidUrl(url) => write!(f, "invalid event URL: {url:?}"),
+            Self::MissingClientIp => write!(
+                f,
+                "no client IP address in the Forwarded or X-Forwarded-For headers, and no peer address"
+            ),
+            Self::InvalidCidr(cidr) => write!(f, "invalid CIDR: {cidr:?}"),
+            Self::UnmatchedRequest(message) => write!(f, "{message}"),
+            Self::InFlightLimitReached { limit } => {
+                write!(
+                    f,
+                    "dropped detached event: {limit} events already in-flight"
+                )
+            }
         }
     }
 }
@@ -44,3 +85,15 @@ impl From<serde_json::Error> for Error {
         Self::SerdeError(e)
     }
 }
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::IoError(e)
+    }
+}
+
+impl From<url::ParseError> for Error {
+    fn from(e: url::ParseError) -> Self {
+        Self::UrlError(e)
+    }
+}
@@ -0,0 +1,40 @@
+use crate::{Error, EventHeaders, EventPayload};
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// Handler called with errors that can't be returned to the caller.
+pub type ErrorHandler = Arc<dyn Fn(Error) + Send + Sync>;
+
+/// Handler called with events that were not sent.
+pub type EventHandler = Arc<dyn Fn(&EventHeaders, &EventPayload) + Send + Sync>;
+
+/// Callbacks registered on a `Plausible` client.
+#[derive(Clone, Default)]
+pub(crate) struct Hooks {
+    pub(crate) on_error: Option<ErrorHandler>,
+    pub(crate) on_bot_filtered: Option<EventHandler>,
+}
+
+impl Hooks {
+    pub(crate) fn error(&self, error: Error) {
+        if let Some(on_error) = &self.on_error {
+            on_error(error);
+        }
+    }
+
+    pub(crate) fn bot_filtered(&self, headers: &EventHeaders, payload: &EventPayload) {
+        if let Some(on_bot_filtered) = &self.on_bot_filtered {
+            on_bot_filtered(headers, payload);
+        }
+    }
+}
+
+impl Debug for Hooks {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hooks")
+            .field("on_error", &self.on_error.is_some())
+            .field("on_bot_filtered", &self.on_bot_filtered.is_some())
+            .finish()
+    }
+}
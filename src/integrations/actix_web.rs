@@ -0,0 +1,274 @@
+//! Pageview tracking for actix-web.
+//!
+//! ```rust no_run
+//! use actix_web::{App, HttpResponse, web};
+//! use plausible_rs::Plausible;
+//! use plausible_rs::integrations::actix_web::PlausibleMiddleware;
+//!
+//! let app = App::new()
+//!     .wrap(PlausibleMiddleware::new(Plausible::new(), String::from("example.com")))
+//!     .route("/", web::get().to(HttpResponse::Ok));
+//! ```
+
+pub use super::{PlausibleRejection, PlausibleRequest, SkipTracking};
+
+use super::is_page;
+use crate::{
+    Error, EventHeaders, EventPayload, PAGEVIEW_EVENT, Plausible, TrustedProxies, UrlFilter,
+};
+use actix_web::dev::{Payload, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{CONTENT_TYPE, HOST, HeaderValue, REFERER};
+use actix_web::http::{Method, StatusCode};
+use actix_web::{FromRequest, HttpMessage, HttpRequest, HttpResponse, ResponseError};
+use http::HeaderMap;
+use std::future::{Future, Ready, ready};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+impl PlausibleRequest {
+    /// Build from an incoming request.
+    ///
+    /// The client IP address is picked according to the `TrustedProxies` in the request's
+    /// extensions, such as those inserted by `PlausibleMiddleware`, or in its app data, or else is
+    /// the first address of the forwarding chain. The peer address is that of the connection.
+    /// The URL's scheme and host are taken from actix-web's `ConnectionInfo` if the peer is a
+    /// trusted proxy, and otherwise from the connection and the `Host` header, as clients can set
+    /// the `Forwarded` and `X-Forwarded-*` headers `ConnectionInfo` reads.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if no client IP address can be determined, or if the request has no host.
+    pub fn from_http_request(request: &HttpRequest) -> Result<Self, Error> {
+        // actix-web's header types are from an older version of `http`
+        let mut headers: HeaderMap = HeaderMap::new();
+        for (name, value) in request.headers() {
+            if let (Ok(name), Ok(value)) = (
+                http::HeaderName::from_bytes(name.as_str().as_bytes()),
+                http::HeaderValue::from_bytes(value.as_bytes()),
+            ) {
+                headers.append(name, value);
+            }
+        }
+
+        let peer = request.peer_addr().map(|addr| addr.ip());
+        let trusted_proxies: Option<TrustedProxies> = request
+            .extensions()
+            .get::<TrustedProxies>()
+            .or_else(|| request.app_data::<TrustedProxies>())
+            .cloned();
+        let event_headers: EventHeaders = match &trusted_proxies {
+            Some(trusted_proxies) => {
+                EventHeaders::from_header_map_trusting(&headers, peer, trusted_proxies)?
+            }
+            None => EventHeaders::from_header_map(&headers, peer)?,
+        };
+
+        let path: &str = request
+            .uri()
+            .path_and_query()
+            .map_or("/", |path| path.as_str());
+        let proxied: bool = trusted_proxies
+            .as_ref()
+            .zip(peer)
+            .is_some_and(|(trusted_proxies, peer)| trusted_proxies.is_trusted(&peer));
+        let url: String = if proxied {
+            let connection_info = request.connection_info();
+            format!(
+                "{}://{}{path}",
+                connection_info.scheme(),
+                connection_info.host()
+            )
+        } else {
+            let scheme: &str = if request.app_config().secure() {
+                "https"
+            } else {
+                "http"
+            };
+            let host: &str = request
+                .headers()
+                .get(HOST)
+                .and_then(|value| value.to_str().ok())
+                .or_else(|| {
+                    request
+                        .uri()
+                        .authority()
+                        .map(actix_web::http::uri::Authority::as_str)
+                })
+                .ok_or_else(|| Error::InvalidUrl(path.to_string()))?;
+            format!("{scheme}://{host}{path}")
+        };
+
+        Ok(Self {
+            headers: event_headers,
+            url,
+            referrer: request
+                .headers()
+                .get(REFERER)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string),
+        })
+    }
+}
+
+impl FromRequest for PlausibleRequest {
+    type Error = PlausibleRejection;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(request: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(Self::from_http_request(request).map_err(|e| {
+            log::warn!(
+                "can't extract `PlausibleRequest` from {}: {e}",
+                request.uri()
+            );
+            PlausibleRejection::from(e)
+        }))
+    }
+}
+
+/// Responds with the rejection's status code only, e.g. `400 Bad Request`.
+impl ResponseError for PlausibleRejection {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(Self::status_code(self).as_u16())
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::new(ResponseError::status_code(self))
+    }
+}
+
+/// Middleware that records a pageview for every HTML page served.
+///
+/// A pageview is recorded for `GET` requests answered with a 2xx status code and an HTML content
+/// type, unless the path isn't tracked by `url_filter` or the response has the `SkipTracking`
+/// extension, e.g. added with `HttpResponse::extensions_mut`. Pageviews are sent with
+/// `Plausible::event_detached`, so responses aren't delayed.
+///
+/// `trusted_proxies`, if set, is inserted into the extensions of every request, so that
+/// `PlausibleRequest` uses it too.
+#[derive(Debug, Clone)]
+pub struct PlausibleMiddleware {
+    pub plausible: Plausible,
+
+    /// Domain of the site pageviews are recorded for.
+    pub domain: String,
+
+    /// Decides which paths are tracked.
+    pub url_filter: UrlFilter,
+
+    /// Decides which address of the forwarding chain is the client's, if set.
+    pub trusted_proxies: Option<TrustedProxies>,
+}
+
+impl PlausibleMiddleware {
+    #[must_use]
+    pub fn new(plausible: Plausible, domain: String) -> Self {
+        Self {
+            plausible,
+            domain,
+            url_filter: UrlFilter::new(),
+            trusted_proxies: None,
+        }
+    }
+
+    pub fn url_filter(&mut self, url_filter: UrlFilter) -> &mut Self {
+        self.url_filter = url_filter;
+        self
+    }
+
+    pub fn trusted_proxies(&mut self, trusted_proxies: TrustedProxies) -> &mut Self {
+        self.trusted_proxies = Some(trusted_proxies);
+        self
+    }
+
+    /// Returns the pageview to record if `request` is answered with an HTML page.
+    fn pageview(&self, request: &ServiceRequest) -> Option<(EventHeaders, EventPayload)> {
+        if let Some(trusted_proxies) = &self.trusted_proxies {
+            request.extensions_mut().insert(trusted_proxies.clone());
+        }
+        if request.method() != Method::GET || !self.url_filter.is_path_tracked(request.path()) {
+            return None;
+        }
+
+        match PlausibleRequest::from_http_request(request.request()) {
+            Ok(plausible_request) => {
+                let payload: EventPayload =
+                    plausible_request.payload(self.domain.clone(), PAGEVIEW_EVENT.to_string());
+                Some((plausible_request.headers, payload))
+            }
+            Err(e) => {
+                log::warn!("not recording pageview of {}: {e}", request.uri());
+                None
+            }
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for PlausibleMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = PlausibleService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(PlausibleService {
+            service,
+            middleware: self.clone(),
+        }))
+    }
+}
+
+/// Service that records pageviews, see `PlausibleMiddleware`.
+#[derive(Debug)]
+pub struct PlausibleService<S> {
+    service: S,
+    middleware: PlausibleMiddleware,
+}
+
+impl<S, B> Service<ServiceRequest> for PlausibleService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let pageview: Option<(EventHeaders, EventPayload)> = self.middleware.pageview(&request);
+        let plausible: Option<Plausible> =
+            pageview.as_ref().map(|_| self.middleware.plausible.clone());
+        let response = self.service.call(request);
+
+        Box::pin(async move {
+            let response: ServiceResponse<B> = response.await?;
+            if let (Some((headers, payload)), Some(plausible)) = (pageview, plausible) {
+                let page: bool = is_page(
+                    response.status().is_success(),
+                    response
+                        .headers()
+                        .get(CONTENT_TYPE)
+                        .map(HeaderValue::as_bytes),
+                    response
+                        .response()
+                        .extensions()
+                        .get::<SkipTracking>()
+                        .is_some(),
+                );
+                if page {
+                    plausible.event_detached(headers, payload);
+                }
+            }
+            Ok(response)
+        })
+    }
+}
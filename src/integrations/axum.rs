@@ -0,0 +1,249 @@
+//! Pageview tracking for axum, and any other `tower` based server.
+//!
+//! Serve the router with `into_make_service_with_connect_info::<SocketAddr>()`, so that the peer
+//! address is known: without it, requests that don't come through a proxy have no client IP
+//! address, and no pageview is recorded for them.
+//!
+//! ```rust no_run
+//! use axum::{Router, routing::get};
+//! use plausible_rs::Plausible;
+//! use plausible_rs::integrations::axum::PlausibleLayer;
+//! use std::net::SocketAddr;
+//!
+//! # async fn run() -> std::io::Result<()> {
+//! let router: Router = Router::new()
+//!     .route("/", get(|| async { axum::response::Html("<h1>Hello</h1>") }))
+//!     .layer(PlausibleLayer::new(Plausible::new(), String::from("example.com")));
+//!
+//! let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
+//! axum::serve(
+//!     listener,
+//!     router.into_make_service_with_connect_info::<SocketAddr>(),
+//! )
+//! .await
+//! # }
+//! ```
+
+pub use super::{PlausibleRejection, PlausibleRequest, SkipTracking};
+
+use super::{forwarded_proto, is_page};
+use crate::{
+    Error, EventHeaders, EventPayload, PAGEVIEW_EVENT, Plausible, TrustedProxies, UrlFilter,
+};
+use axum::extract::{ConnectInfo, FromRequestParts};
+use axum::response::{IntoResponse, IntoResponseParts, Response, ResponseParts};
+use http::header::{CONTENT_TYPE, HOST, REFERER};
+use http::request::Parts;
+use http::{Extensions, HeaderMap, HeaderValue, Method, Request, Uri};
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+impl PlausibleRequest {
+    /// Build from the head of an incoming request.
+    ///
+    /// The client IP address is picked according to the `TrustedProxies` in the request's
+    /// extensions, such as those inserted by `PlausibleLayer`, or else is the first address of the
+    /// forwarding chain. The peer address is taken from axum's `ConnectInfo<SocketAddr>`.
+    /// The URL's host is taken from the `Host` header, and its scheme from `X-Forwarded-Proto` if
+    /// the peer is a trusted proxy, defaulting to `http`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if no client IP address can be determined, or if the request has no host.
+    pub fn from_http(
+        headers: &HeaderMap,
+        uri: &Uri,
+        extensions: &Extensions,
+    ) -> Result<Self, Error> {
+        let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+
+        let peer: Option<IpAddr> = extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip())
+            .or_else(|| extensions.get::<SocketAddr>().map(SocketAddr::ip));
+        let trusted_proxies: Option<&TrustedProxies> = extensions.get::<TrustedProxies>();
+        let event_headers: EventHeaders = match trusted_proxies {
+            Some(trusted_proxies) => {
+                EventHeaders::from_header_map_trusting(headers, peer, trusted_proxies)?
+            }
+            None => EventHeaders::from_header_map(headers, peer)?,
+        };
+
+        // clients can set `X-Forwarded-Proto` too, so only proxies are believed
+        let proxied: bool = trusted_proxies
+            .zip(peer)
+            .is_some_and(|(trusted_proxies, peer)| trusted_proxies.is_trusted(&peer));
+        let scheme: &str = uri
+            .scheme_str()
+            .or_else(|| forwarded_proto(header("x-forwarded-proto")).filter(|_| proxied))
+            .unwrap_or("http");
+        let host: &str = uri
+            .authority()
+            .map(http::uri::Authority::as_str)
+            .or_else(|| header(HOST.as_str()))
+            .ok_or_else(|| Error::InvalidUrl(uri.to_string()))?;
+        let path: &str = uri.path_and_query().map_or("/", |path| path.as_str());
+
+        Ok(Self {
+            headers: event_headers,
+            url: format!("{scheme}://{host}{path}"),
+            referrer: header(REFERER.as_str()).map(str::to_string),
+        })
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for PlausibleRequest {
+    type Rejection = PlausibleRejection;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Self::from_http(&parts.headers, &parts.uri, &parts.extensions).map_err(|e| {
+            log::warn!("can't extract `PlausibleRequest` from {}: {e}", parts.uri);
+            PlausibleRejection::from(e)
+        })
+    }
+}
+
+/// Responds with the rejection's status code only, e.g. `400 Bad Request`.
+impl IntoResponse for PlausibleRejection {
+    fn into_response(self) -> Response {
+        self.status_code().into_response()
+    }
+}
+
+/// Return `SkipTracking` from a handler to opt its route out, e.g. `(SkipTracking, Html(page))`.
+impl IntoResponseParts for SkipTracking {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        res.extensions_mut().insert(self);
+        Ok(res)
+    }
+}
+
+/// `tower::Layer` that records a pageview for every HTML page served.
+///
+/// A pageview is recorded for `GET` requests answered with a 2xx status code and an HTML content
+/// type, unless the path isn't tracked by `url_filter` or the response has the `SkipTracking`
+/// extension. Pageviews are sent with `Plausible::event_detached`, so responses aren't delayed.
+///
+/// `trusted_proxies`, if set, is inserted into the extensions of every request, so that
+/// `PlausibleRequest` uses it too.
+#[derive(Debug, Clone)]
+pub struct PlausibleLayer {
+    pub plausible: Plausible,
+
+    /// Domain of the site pageviews are recorded for.
+    pub domain: String,
+
+    /// Decides which paths are tracked.
+    pub url_filter: UrlFilter,
+
+    /// Decides which address of the forwarding chain is the client's, if set.
+    pub trusted_proxies: Option<TrustedProxies>,
+}
+
+impl PlausibleLayer {
+    #[must_use]
+    pub fn new(plausible: Plausible, domain: String) -> Self {
+        Self {
+            plausible,
+            domain,
+            url_filter: UrlFilter::new(),
+            trusted_proxies: None,
+        }
+    }
+
+    pub fn url_filter(&mut self, url_filter: UrlFilter) -> &mut Self {
+        self.url_filter = url_filter;
+        self
+    }
+
+    pub fn trusted_proxies(&mut self, trusted_proxies: TrustedProxies) -> &mut Self {
+        self.trusted_proxies = Some(trusted_proxies);
+        self
+    }
+
+    /// Returns the pageview to record if `request` is answered with an HTML page.
+    fn pageview<B>(&self, request: &mut Request<B>) -> Option<(EventHeaders, EventPayload)> {
+        if let Some(trusted_proxies) = &self.trusted_proxies {
+            request.extensions_mut().insert(trusted_proxies.clone());
+        }
+        if request.method() != Method::GET || !self.url_filter.is_path_tracked(request.uri().path())
+        {
+            return None;
+        }
+
+        match PlausibleRequest::from_http(request.headers(), request.uri(), request.extensions()) {
+            Ok(plausible_request) => {
+                let payload: EventPayload =
+                    plausible_request.payload(self.domain.clone(), PAGEVIEW_EVENT.to_string());
+                Some((plausible_request.headers, payload))
+            }
+            Err(e) => {
+                log::warn!("not recording pageview of {}: {e}", request.uri());
+                None
+            }
+        }
+    }
+}
+
+impl<S> Layer<S> for PlausibleLayer {
+    type Service = PlausibleService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        PlausibleService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service that records pageviews, see `PlausibleLayer`.
+#[derive(Debug, Clone)]
+pub struct PlausibleService<S> {
+    inner: S,
+    layer: PlausibleLayer,
+}
+
+impl<S, B, ResBody> Service<Request<B>> for PlausibleService<S>
+where
+    S: Service<Request<B>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<B>) -> Self::Future {
+        let pageview: Option<(EventHeaders, EventPayload)> = self.layer.pageview(&mut request);
+        let plausible: Option<Plausible> = pageview.as_ref().map(|_| self.layer.plausible.clone());
+        let response = self.inner.call(request);
+
+        Box::pin(async move {
+            let response: http::Response<ResBody> = response.await?;
+            if let (Some((headers, payload)), Some(plausible)) = (pageview, plausible) {
+                let page: bool = is_page(
+                    response.status().is_success(),
+                    response
+                        .headers()
+                        .get(CONTENT_TYPE)
+                        .map(HeaderValue::as_bytes),
+                    response.extensions().get::<SkipTracking>().is_some(),
+                );
+                if page {
+                    plausible.event_detached(headers, payload);
+                }
+            }
+            Ok(response)
+        })
+    }
+}
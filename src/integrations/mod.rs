@@ -0,0 +1,110 @@
+//! Automatic event tracking for web and gRPC frameworks, each enabled by the feature of the same name.
+
+#[cfg(feature = "actix-web")]
+pub mod actix_web;
+#[cfg(feature = "axum")]
+pub mod axum;
+#[cfg(feature = "rocket")]
+pub mod rocket;
+#[cfg(feature = "tonic")]
+pub mod tonic;
+
+use crate::{Error, EventHeaders, EventPayload};
+use http::StatusCode;
+use std::fmt::{self, Display, Formatter};
+
+/// An incoming request, as needed to record an event for it.
+///
+/// Each integration extracts it from its framework's requests.
+#[derive(Debug, Clone)]
+pub struct PlausibleRequest {
+    pub headers: EventHeaders,
+
+    /// Absolute URL of the requested page.
+    pub url: String,
+
+    /// The `Referer` header, if any.
+    pub referrer: Option<String>,
+}
+
+impl PlausibleRequest {
+    /// Build the payload of an event named `name`, for the requested page of the site `domain`.
+    #[must_use]
+    pub fn payload(&self, domain: String, name: String) -> EventPayload {
+        let mut builder = EventPayload::builder(domain, name, self.url.clone());
+        if let Some(referrer) = &self.referrer {
+            builder.referrer(referrer.clone());
+        }
+        builder.build()
+    }
+}
+
+/// Why a `PlausibleRequest` couldn't be extracted from an incoming request.
+///
+/// Integrations respond to it with `Self::status_code` and an empty body, so that error details
+/// aren't sent to clients.
+#[derive(Debug)]
+pub enum PlausibleRejection {
+    /// The request has no client IP address or no host, see `Error::MissingClientIp` and
+    /// `Error::InvalidUrl`.
+    InvalidRequest(Error),
+
+    /// The integration isn't set up, e.g. Rocket's `PlausibleTracker` is used without attaching
+    /// `PlausibleFairing`.
+    Misconfigured,
+}
+
+impl PlausibleRejection {
+    /// Returns the status code to respond with: `400 Bad Request` for an `InvalidRequest`, and
+    /// `500 Internal Server Error` if `Misconfigured`.
+    #[must_use]
+    pub const fn status_code(&self) -> StatusCode {
+        match self {
+            Self::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+            Self::Misconfigured => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<Error> for PlausibleRejection {
+    fn from(e: Error) -> Self {
+        Self::InvalidRequest(e)
+    }
+}
+
+impl std::error::Error for PlausibleRejection {}
+
+impl Display for PlausibleRejection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidRequest(e) => write!(f, "can't record events for this request: {e}"),
+            Self::Misconfigured => write!(f, "the Plausible integration isn't set up"),
+        }
+    }
+}
+
+/// Response extension that stops an integration from recording a pageview.
+///
+/// Add it to a handler's response to opt its route out.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SkipTracking;
+
+/// Returns the scheme reported by the `X-Forwarded-Proto` header of a request, if any.
+///
+/// Only read it when the request comes from a trusted proxy, as clients can set it too.
+#[cfg(any(feature = "axum", feature = "rocket"))]
+fn forwarded_proto(value: Option<&str>) -> Option<&str> {
+    value
+        .and_then(|value| value.split(',').next())
+        .map(str::trim)
+        .filter(|scheme| !scheme.is_empty())
+}
+
+/// Returns whether a response is a successful HTML page, which is tracked as a pageview.
+#[cfg(any(feature = "actix-web", feature = "axum", feature = "rocket"))]
+fn is_page(success: bool, content_type: Option<&[u8]>, skipped: bool) -> bool {
+    let html: bool = content_type
+        .and_then(|value| std::str::from_utf8(value).ok())
+        .is_some_and(|value| value.trim_start().starts_with("text/html"));
+    success && html && !skipped
+}
@@ -0,0 +1,282 @@
+//! Pageview tracking for Rocket.
+//!
+//! `PlausibleFairing` is configured from the `plausible` key of Rocket's figment, e.g. in
+//! `Rocket.toml`:
+//!
+//! ```toml
+//! [default.plausible]
+//! domain = "example.com"
+//! base_url = "https://plausible.io"
+//! exclude = ["/admin/**"]
+//! trusted_proxies = ["10.0.0.0/8"]
+//! ```
+
+pub use super::{PlausibleRejection, PlausibleRequest};
+
+use super::{forwarded_proto, is_page};
+use crate::{
+    BASE_URL, Error, EventHeaders, EventOutcome, EventPayload, PAGEVIEW_EVENT, Plausible,
+    PlausibleBuilder, PrivacySignals, TrustedProxies, UrlFilter,
+};
+use rocket::fairing::{self, Fairing, Info, Kind};
+use rocket::http::{Method, Status};
+use rocket::request::{FromRequest, Outcome};
+use rocket::{Build, Request, Response, Rocket};
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+/// Key of `PlausibleConfig` in Rocket's figment.
+pub const CONFIG_KEY: &str = "plausible";
+
+/// Configuration of `PlausibleFairing`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlausibleConfig {
+    /// Domain of the site events are recorded for.
+    pub domain: String,
+
+    /// Base URL of the Plausible Analytics instance.
+    ///
+    /// Defaults to `BASE_URL`.
+    #[serde(default = "default_base_url")]
+    pub base_url: String,
+
+    /// Patterns of the paths that aren't tracked, see `UrlFilter`.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+
+    /// Networks of the proxies in front of the application, e.g. `10.0.0.0/8`, whose
+    /// `X-Forwarded-Proto` header is trusted.
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
+}
+
+fn default_base_url() -> String {
+    BASE_URL.to_string()
+}
+
+impl PlausibleRequest {
+    /// Build from an incoming request.
+    ///
+    /// The client IP address is `Request::client_ip`, so Rocket's `ip_header` setting decides
+    /// which proxy header is trusted. The URL's host is taken from the `Host` header, and its
+    /// scheme from `X-Forwarded-Proto` if the peer is in one of the networks of
+    /// `trusted_proxies`, defaulting to `https` if Rocket serves TLS itself and to `http`
+    /// otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if no client IP address can be determined, or if the request has no host.
+    pub fn from_rocket_request(
+        request: &Request<'_>,
+        trusted_proxies: &TrustedProxies,
+    ) -> Result<Self, Error> {
+        let header = |name: &str| request.headers().get_one(name);
+
+        let client_ip: IpAddr = request.client_ip().ok_or(Error::MissingClientIp)?;
+        let headers: EventHeaders = EventHeaders::new(
+            header("user-agent").unwrap_or_default().to_string(),
+            client_ip.to_string(),
+        )
+        .with_privacy_signals(PrivacySignals::from_header_values(
+            header("dnt"),
+            header("sec-gpc"),
+        ));
+
+        // clients can set `X-Forwarded-Proto` too, so only proxies are believed
+        let proxied: bool = request
+            .remote()
+            .is_some_and(|peer| trusted_proxies.is_trusted(&peer.ip()));
+        let scheme: &str = forwarded_proto(header("x-forwarded-proto"))
+            .filter(|_| proxied)
+            .unwrap_or(if request.rocket().config().tls_enabled() {
+                "https"
+            } else {
+                "http"
+            });
+        let host = request
+            .host()
+            .ok_or_else(|| Error::InvalidUrl(request.uri().to_string()))?;
+
+        Ok(Self {
+            headers,
+            url: format!("{scheme}://{host}{}", request.uri()),
+            referrer: header("referer").map(str::to_string),
+        })
+    }
+}
+
+/// State managed by Rocket once `PlausibleFairing` is attached.
+struct Tracking {
+    plausible: Plausible,
+    domain: String,
+    url_filter: UrlFilter,
+    trusted_proxies: TrustedProxies,
+}
+
+/// Fairing that records a pageview for every HTML page served.
+///
+/// A pageview is recorded for `GET` requests answered with a 2xx status code and an HTML content
+/// type, unless the path is excluded by `PlausibleConfig::exclude`. Pageviews are sent with
+/// `Plausible::event_detached`, so responses aren't delayed.
+///
+/// Attaching it also enables the `PlausibleTracker` request guard.
+#[derive(Debug, Clone, Default)]
+pub struct PlausibleFairing {
+    builder: PlausibleBuilder,
+}
+
+impl PlausibleFairing {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new fairing whose client is built with `builder`, with the base URL from the
+    /// configuration.
+    #[must_use]
+    pub const fn with_builder(builder: PlausibleBuilder) -> Self {
+        Self { builder }
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for PlausibleFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Plausible Analytics",
+            kind: Kind::Ignite | Kind::Response,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let config: PlausibleConfig = match rocket.figment().extract_inner(CONFIG_KEY) {
+            Ok(config) => config,
+            Err(e) => {
+                log::error!("invalid `{CONFIG_KEY}` configuration: {e}");
+                return Err(rocket);
+            }
+        };
+
+        let trusted_proxies: TrustedProxies =
+            match TrustedProxies::with_cidrs(&config.trusted_proxies) {
+                Ok(trusted_proxies) => trusted_proxies,
+                Err(e) => {
+                    log::error!("invalid `{CONFIG_KEY}` configuration: {e}");
+                    return Err(rocket);
+                }
+            };
+        let mut url_filter: UrlFilter = UrlFilter::new();
+        for pattern in &config.exclude {
+            url_filter.exclude(pattern);
+        }
+        let plausible: Plausible = self.builder.clone().base_url(config.base_url).build();
+        Ok(rocket.manage(Tracking {
+            plausible,
+            domain: config.domain,
+            url_filter,
+            trusted_proxies,
+        }))
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let Some(tracking) = request.rocket().state::<Tracking>() else {
+            return;
+        };
+        let page: bool = request.method() == Method::Get
+            && tracking
+                .url_filter
+                .is_path_tracked(request.uri().path().as_str())
+            && is_page(
+                response.status().class().is_success(),
+                response
+                    .headers()
+                    .get_one("content-type")
+                    .map(str::as_bytes),
+                false,
+            );
+        if !page {
+            return;
+        }
+
+        match PlausibleRequest::from_rocket_request(request, &tracking.trusted_proxies) {
+            Ok(plausible_request) => {
+                let payload: EventPayload =
+                    plausible_request.payload(tracking.domain.clone(), PAGEVIEW_EVENT.to_string());
+                tracking
+                    .plausible
+                    .event_detached(plausible_request.headers, payload);
+            }
+            Err(e) => log::warn!("not recording pageview of {}: {e}", request.uri()),
+        }
+    }
+}
+
+/// Request guard that records events for the current request.
+///
+/// Requires `PlausibleFairing` to be attached.
+#[derive(Debug, Clone)]
+pub struct PlausibleTracker {
+    plausible: Plausible,
+    domain: String,
+    request: PlausibleRequest,
+}
+
+impl PlausibleTracker {
+    #[must_use]
+    pub const fn request(&self) -> &PlausibleRequest {
+        &self.request
+    }
+
+    /// Build the payload of an event named `name`, for the requested page.
+    #[must_use]
+    pub fn payload(&self, name: String) -> EventPayload {
+        self.request.payload(self.domain.clone(), name)
+    }
+
+    /// Records an event for the current request, see `Plausible::event`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the event couldn't be sent.
+    pub async fn event(&self, payload: EventPayload) -> Result<EventOutcome, Error> {
+        self.plausible
+            .event(self.request.headers.clone(), payload)
+            .await
+    }
+
+    /// Records an event for the current request without waiting for it to be sent, see
+    /// `Plausible::event_detached`.
+    pub fn event_detached(&self, payload: EventPayload) {
+        self.plausible
+            .event_detached(self.request.headers.clone(), payload);
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for PlausibleTracker {
+    type Error = PlausibleRejection;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let Some(tracking) = request.rocket().state::<Tracking>() else {
+            log::error!("`PlausibleTracker` requires `PlausibleFairing` to be attached");
+            return Outcome::Error((
+                Status::InternalServerError,
+                PlausibleRejection::Misconfigured,
+            ));
+        };
+        match PlausibleRequest::from_rocket_request(request, &tracking.trusted_proxies) {
+            Ok(plausible_request) => Outcome::Success(Self {
+                plausible: tracking.plausible.clone(),
+                domain: tracking.domain.clone(),
+                request: plausible_request,
+            }),
+            Err(e) => {
+                log::warn!(
+                    "can't extract `PlausibleTracker` from {}: {e}",
+                    request.uri()
+                );
+                Outcome::Error((Status::BadRequest, PlausibleRejection::from(e)))
+            }
+        }
+    }
+}
@@ -0,0 +1,278 @@
+//! RPC tracking for tonic, and any other `tower` based gRPC server.
+//!
+//! ```rust no_run
+//! use plausible_rs::Plausible;
+//! use plausible_rs::integrations::tonic::PlausibleLayer;
+//! use tonic::service::Routes;
+//! use tonic::transport::Server;
+//!
+//! # async fn run(routes: Routes) -> Result<(), tonic::transport::Error> {
+//! // `routes` holds the services, e.g. `Routes::new(GreeterServer::new(greeter))`
+//! Server::builder()
+//!     .layer(PlausibleLayer::new(Plausible::new(), String::from("example.com")))
+//!     .add_routes(routes)
+//!     .serve("0.0.0.0:50051".parse().unwrap())
+//!     .await
+//! # }
+//! ```
+
+use crate::{Error, EventHeaders, EventPayload, Plausible, PropValue, TrustedProxies};
+use http::{HeaderMap, Request, Response};
+use http_body::{Body, Frame, SizeHint};
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+use tonic::Code;
+use tonic::transport::server::TcpConnectInfo;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Name of the event recorded for every RPC.
+pub const RPC_EVENT: &str = "RPC";
+
+/// Prop holding the gRPC status code of an RPC, e.g. `NotFound`.
+pub const STATUS_PROP: &str = "grpc_status";
+
+/// Services that aren't tracked by default: gRPC health checking and server reflection.
+pub const DEFAULT_EXCLUDED_SERVICES: &[&str] = &[
+    "grpc.health.v1.Health",
+    "grpc.reflection.v1.ServerReflection",
+    "grpc.reflection.v1alpha.ServerReflection",
+];
+
+/// `tower::Layer` that records every RPC as a custom event.
+///
+/// The event's URL is `app://{domain}/{service}/{method}`, e.g.
+/// `app://example.com/helloworld.Greeter/SayHello`, and its `STATUS_PROP` prop is the RPC's
+/// status code, read from the `grpc-status` trailer once the response has been sent.
+/// Responses dropped before that, e.g. because the client cancelled the RPC, are recorded as
+/// `Cancelled`. Events are sent with `Plausible::event_detached`, so responses aren't delayed.
+///
+/// Event headers are taken from the `user-agent`, `forwarded` and `x-forwarded-for` metadata,
+/// falling back to the peer address of the connection: tonic's `TcpConnectInfo`, or a
+/// `SocketAddr` in the request's extensions for other servers.
+#[derive(Debug, Clone)]
+pub struct PlausibleLayer {
+    pub plausible: Plausible,
+
+    /// Domain of the site events are recorded for.
+    pub domain: String,
+
+    /// Fully qualified names of the services whose RPCs aren't tracked.
+    ///
+    /// Defaults to `DEFAULT_EXCLUDED_SERVICES`.
+    pub excluded_services: Vec<String>,
+
+    /// Decides which address of the forwarding chain is the client's, if set.
+    pub trusted_proxies: Option<TrustedProxies>,
+}
+
+impl PlausibleLayer {
+    #[must_use]
+    pub fn new(plausible: Plausible, domain: String) -> Self {
+        Self {
+            plausible,
+            domain,
+            excluded_services: DEFAULT_EXCLUDED_SERVICES
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            trusted_proxies: None,
+        }
+    }
+
+    /// Don't track the RPCs of `service`, e.g. `helloworld.Greeter`.
+    pub fn exclude_service(&mut self, service: &str) -> &mut Self {
+        self.excluded_services.push(service.to_string());
+        self
+    }
+
+    pub fn trusted_proxies(&mut self, trusted_proxies: TrustedProxies) -> &mut Self {
+        self.trusted_proxies = Some(trusted_proxies);
+        self
+    }
+
+    /// Returns the event to record for `request`, without its status.
+    fn rpc<B>(&self, request: &Request<B>) -> Option<PendingEvent> {
+        let (service, method) = request.uri().path().strip_prefix('/')?.split_once('/')?;
+        if method.is_empty() || self.excluded_services.iter().any(|s| s == service) {
+            return None;
+        }
+
+        let peer: Option<IpAddr> = request
+            .extensions()
+            .get::<TcpConnectInfo>()
+            .and_then(TcpConnectInfo::remote_addr)
+            .or_else(|| request.extensions().get::<SocketAddr>().copied())
+            .map(|addr: SocketAddr| addr.ip());
+        let headers: Result<EventHeaders, Error> = match &self.trusted_proxies {
+            Some(trusted_proxies) => {
+                EventHeaders::from_header_map_trusting(request.headers(), peer, trusted_proxies)
+            }
+            None => EventHeaders::from_header_map(request.headers(), peer),
+        };
+        match headers {
+            Ok(headers) => Some(PendingEvent {
+                plausible: self.plausible.clone(),
+                headers,
+                payload: EventPayload::builder(
+                    self.domain.clone(),
+                    RPC_EVENT.to_string(),
+                    format!("app://{}/{service}/{method}", self.domain),
+                )
+                .build(),
+            }),
+            Err(e) => {
+                log::warn!("not recording RPC {}: {e}", request.uri().path());
+                None
+            }
+        }
+    }
+}
+
+impl<S> Layer<S> for PlausibleLayer {
+    type Service = PlausibleService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        PlausibleService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service that records RPCs, see `PlausibleLayer`.
+#[derive(Debug, Clone)]
+pub struct PlausibleService<S> {
+    inner: S,
+    layer: PlausibleLayer,
+}
+
+impl<S, B, ResBody> Service<Request<B>> for PlausibleService<S>
+where
+    S: Service<Request<B>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = Response<TrackedBody<ResBody>>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let mut pending: Option<PendingEvent> = self.layer.rpc(&request);
+        let response = self.inner.call(request);
+
+        Box::pin(async move {
+            let response: Response<ResBody> = response.await?;
+
+            // errors are usually sent as trailers-only responses, with the status in the headers
+            if let Some(code) = grpc_status(response.headers()) {
+                if let Some(pending) = pending.take() {
+                    pending.record(code);
+                }
+            }
+
+            Ok(response.map(|body| TrackedBody {
+                inner: Box::pin(body),
+                pending,
+            }))
+        })
+    }
+}
+
+/// An RPC whose event is recorded once its status is known.
+#[derive(Debug)]
+struct PendingEvent {
+    plausible: Plausible,
+    headers: EventHeaders,
+    payload: EventPayload,
+}
+
+impl PendingEvent {
+    fn record(mut self, code: Code) {
+        // events are sent from a spawned task, e.g. bodies may be dropped after the runtime shut down
+        if tokio::runtime::Handle::try_current().is_err() {
+            log::warn!(
+                "not recording RPC {} outside of a Tokio runtime",
+                self.payload.url
+            );
+            return;
+        }
+
+        self.payload.props.get_or_insert_with(HashMap::new).insert(
+            STATUS_PROP.to_string(),
+            PropValue::from(format!("{code:?}")),
+        );
+        self.plausible.event_detached(self.headers, self.payload);
+    }
+}
+
+/// Response body that records its RPC once the `grpc-status` trailer is sent.
+pub struct TrackedBody<B> {
+    inner: Pin<Box<B>>,
+    pending: Option<PendingEvent>,
+}
+
+impl<B> TrackedBody<B> {
+    fn record(&mut self, code: Code) {
+        if let Some(pending) = self.pending.take() {
+            pending.record(code);
+        }
+    }
+}
+
+impl<B: Body> Body for TrackedBody<B> {
+    type Data = B::Data;
+    type Error = B::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this: &mut Self = self.get_mut();
+        let frame = ready!(this.inner.as_mut().poll_frame(cx));
+        match &frame {
+            Some(Ok(frame)) => {
+                if let Some(code) = frame.trailers_ref().and_then(grpc_status) {
+                    this.record(code);
+                }
+            }
+            Some(Err(_)) => this.record(Code::Internal),
+            None => this.record(Code::Unknown),
+        }
+        Poll::Ready(frame)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl<B> Drop for TrackedBody<B> {
+    fn drop(&mut self) {
+        self.record(Code::Cancelled);
+    }
+}
+
+impl<B> std::fmt::Debug for TrackedBody<B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TrackedBody")
+            .field("pending", &self.pending)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the status code of the `grpc-status` header or trailer.
+fn grpc_status(headers: &HeaderMap) -> Option<Code> {
+    let code: i32 = headers.get("grpc-status")?.to_str().ok()?.parse().ok()?;
+    Some(Code::from(code))
+}
@@ -40,9 +40,27 @@
 //! For more examples, check out the `examples` directory within the repository.
 
 mod api;
+mod cassette;
 mod error;
+mod hash;
+mod hooks;
+#[cfg(any(
+    feature = "actix-web",
+    feature = "axum",
+    feature = "rocket",
+    feature = "tonic"
+))]
+pub mod integrations;
 mod plausible_analytics;
+mod plausible_builder;
+mod sink;
+#[cfg(feature = "test-util")]
+pub mod test_util;
 
 pub use api::*;
+pub use cassette::*;
 pub use error::*;
+pub use hooks::*;
 pub use plausible_analytics::*;
+pub use plausible_builder::*;
+pub use sink::*;
@@ -1,4 +1,12 @@
-use reqwest::Client;
+use crate::cassette::{RawResponse, send};
+use crate::{
+    BotFilter, Cassette, ConsentPolicy, Deduplication, Error, Hooks, PlausibleBuilder,
+    SamplingPolicy, SiteDomain, TrustedProxies, UrlFilter, UrlNormalizer,
+};
+use reqwest::{Client, Request};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
 
 pub const BASE_URL: &str = "https://plausible.io";
 
@@ -7,6 +15,20 @@ pub const BASE_URL: &str = "https://plausible.io";
 pub struct Plausible {
     pub(crate) client: Client,
     pub(crate) base_url: String,
+    pub(crate) dry_run: bool,
+    pub(crate) debug: bool,
+    pub(crate) max_in_flight: usize,
+    pub(crate) in_flight: Arc<Semaphore>,
+    pub(crate) hooks: Hooks,
+    pub(crate) bot_filter: Option<BotFilter>,
+    pub(crate) consent_policy: ConsentPolicy,
+    pub(crate) rollup_domain: Option<SiteDomain>,
+    pub(crate) deduplication: Option<Deduplication>,
+    pub(crate) sampling: Option<SamplingPolicy>,
+    pub(crate) url_filters: HashMap<String, UrlFilter>,
+    pub(crate) url_normalizer: Option<UrlNormalizer>,
+    pub(crate) trusted_proxies: Option<TrustedProxies>,
+    pub(crate) cassette: Option<Cassette>,
 }
 
 impl Plausible {
@@ -16,18 +38,25 @@ impl Plausible {
     /// [webpki roots](https://github.com/rustls/webpki-roots) by default.
     #[must_use]
     pub fn new() -> Self {
-        Self {
-            client: Client::new(),
-            base_url: BASE_URL.to_string(),
-        }
+        PlausibleBuilder::new().build()
     }
 
     /// Create a new Plausible Analytics client with a given `reqwest::Client`.
     #[must_use]
     pub fn new_with_client(client: Client) -> Self {
-        Self {
-            client,
-            base_url: BASE_URL.to_string(),
+        PlausibleBuilder::new().client(client).build()
+    }
+
+    #[must_use]
+    pub fn builder() -> PlausibleBuilder {
+        PlausibleBuilder::new()
+    }
+
+    /// Sends `request`, or records or replays it if the client was built with a `Cassette`.
+    pub(crate) async fn execute(&self, request: Request) -> Result<RawResponse, Error> {
+        match &self.cassette {
+            Some(cassette) => cassette.execute(&self.client, request).await,
+            None => send(&self.client, request).await,
         }
     }
 }
@@ -0,0 +1,210 @@
+use crate::{
+    BASE_URL, BotFilter, Cassette, ConsentPolicy, Deduplication, Error, EventHeaders, EventPayload,
+    Hooks, Plausible, SamplingPolicy, SiteDomain, TrustedProxies, UrlFilter, UrlNormalizer,
+};
+use reqwest::Client;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+/// Default maximum number of detached events that may be in-flight at once.
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 64;
+
+/// Plausible Analytics client configuration.
+///
+/// This is a Builder for `Plausible`.
+#[derive(Debug, Clone)]
+pub struct PlausibleBuilder {
+    /// HTTP client used to send requests.
+    pub client: Client,
+
+    /// Base URL of the Plausible Analytics instance, e.g. `https://plausible.io`.
+    ///
+    /// Set this when self-hosting Plausible.
+    pub base_url: String,
+
+    /// Whether events are built and logged instead of being sent.
+    ///
+    /// Useful during development and in CI, to see exactly what would be sent.
+    pub dry_run: bool,
+
+    /// Whether the bodies of responses from Plausible are logged.
+    pub debug: bool,
+
+    /// Maximum number of events sent with `Plausible::event_detached` that may be in-flight at
+    /// once.
+    pub max_in_flight: usize,
+
+    /// Drops events from bots, if set.
+    pub bot_filter: Option<BotFilter>,
+
+    /// What to do with events from visitors who opted out of tracking.
+    pub consent_policy: ConsentPolicy,
+
+    /// Site every event is also recorded for, if set.
+    ///
+    /// See: <https://plausible.io/docs/plausible-script#can-i-send-stats-to-multiple-dashboards-at-the-same-time>
+    pub rollup_domain: Option<SiteDomain>,
+
+    /// Suppresses repeats of the same event, if set.
+    pub deduplication: Option<Deduplication>,
+
+    /// Sends only a fraction of high-volume events, if set.
+    pub sampling: Option<SamplingPolicy>,
+
+    /// Decides which pages are tracked, by site domain.
+    pub url_filters: HashMap<String, UrlFilter>,
+
+    /// Rewrites the URL of every event before it is sent, if set.
+    pub url_normalizer: Option<UrlNormalizer>,
+
+    /// Rewrites the `x_forwarded_for` of every event to the client's address of its chain, if set.
+    pub trusted_proxies: Option<TrustedProxies>,
+
+    /// Records or replays every request, if set.
+    pub cassette: Option<Cassette>,
+
+    pub(crate) hooks: Hooks,
+}
+
+impl PlausibleBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+            base_url: BASE_URL.to_string(),
+            dry_run: false,
+            debug: false,
+            max_in_flight: DEFAULT_MAX_IN_FLIGHT,
+            bot_filter: None,
+            consent_policy: ConsentPolicy::Ignore,
+            rollup_domain: None,
+            deduplication: None,
+            sampling: None,
+            url_filters: HashMap::new(),
+            url_normalizer: None,
+            trusted_proxies: None,
+            cassette: None,
+            hooks: Hooks::default(),
+        }
+    }
+
+    pub fn client(&mut self, client: Client) -> &mut Self {
+        self.client = client;
+        self
+    }
+
+    pub fn base_url(&mut self, base_url: String) -> &mut Self {
+        self.base_url = base_url;
+        self
+    }
+
+    pub fn dry_run(&mut self, dry_run: bool) -> &mut Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub fn debug(&mut self, debug: bool) -> &mut Self {
+        self.debug = debug;
+        self
+    }
+
+    pub fn max_in_flight(&mut self, max_in_flight: usize) -> &mut Self {
+        self.max_in_flight = max_in_flight;
+        self
+    }
+
+    pub fn bot_filter(&mut self, bot_filter: BotFilter) -> &mut Self {
+        self.bot_filter = Some(bot_filter);
+        self
+    }
+
+    pub fn consent_policy(&mut self, consent_policy: ConsentPolicy) -> &mut Self {
+        self.consent_policy = consent_policy;
+        self
+    }
+
+    pub fn rollup_domain(&mut self, rollup_domain: SiteDomain) -> &mut Self {
+        self.rollup_domain = Some(rollup_domain);
+        self
+    }
+
+    pub fn deduplication(&mut self, deduplication: Deduplication) -> &mut Self {
+        self.deduplication = Some(deduplication);
+        self
+    }
+
+    pub fn sampling(&mut self, sampling: SamplingPolicy) -> &mut Self {
+        self.sampling = Some(sampling);
+        self
+    }
+
+    /// Decides which pages of the site `domain` are tracked.
+    pub fn url_filter(&mut self, domain: String, url_filter: UrlFilter) -> &mut Self {
+        self.url_filters.insert(domain, url_filter);
+        self
+    }
+
+    pub fn url_normalizer(&mut self, url_normalizer: UrlNormalizer) -> &mut Self {
+        self.url_normalizer = Some(url_normalizer);
+        self
+    }
+
+    pub fn trusted_proxies(&mut self, trusted_proxies: TrustedProxies) -> &mut Self {
+        self.trusted_proxies = Some(trusted_proxies);
+        self
+    }
+
+    /// Records requests to, or replays them from, a cassette file.
+    pub fn cassette(&mut self, cassette: Cassette) -> &mut Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Registers a handler that is called with every error from `Plausible::event_detached`.
+    pub fn on_error<F>(&mut self, handler: F) -> &mut Self
+    where
+        F: Fn(Error) + Send + Sync + 'static,
+    {
+        self.hooks.on_error = Some(Arc::new(handler));
+        self
+    }
+
+    /// Registers a handler that is called with every event dropped by the `BotFilter`.
+    pub fn on_bot_filtered<F>(&mut self, handler: F) -> &mut Self
+    where
+        F: Fn(&EventHeaders, &EventPayload) + Send + Sync + 'static,
+    {
+        self.hooks.on_bot_filtered = Some(Arc::new(handler));
+        self
+    }
+
+    #[must_use]
+    pub fn build(&self) -> Plausible {
+        Plausible {
+            client: self.client.clone(),
+            base_url: self.base_url.clone(),
+            dry_run: self.dry_run,
+            debug: self.debug,
+            max_in_flight: self.max_in_flight,
+            in_flight: Arc::new(Semaphore::new(self.max_in_flight)),
+            hooks: self.hooks.clone(),
+            bot_filter: self.bot_filter.clone(),
+            consent_policy: self.consent_policy,
+            rollup_domain: self.rollup_domain.clone(),
+            deduplication: self.deduplication.clone(),
+            sampling: self.sampling.clone(),
+            url_filters: self.url_filters.clone(),
+            url_normalizer: self.url_normalizer.clone(),
+            trusted_proxies: self.trusted_proxies.clone(),
+            cassette: self.cassette.clone(),
+        }
+    }
+}
+
+impl Default for PlausibleBuilder {
+    /// Defaults to `Self::new()`.
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,16 @@
+use crate::{EventHeaders, EventPayload};
+use serde::{Deserialize, Serialize};
+
+/// An event as recorded by an `EventSink`, e.g. one line of the file written by `FileSink`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventRecord {
+    pub headers: EventHeaders,
+    pub payload: EventPayload,
+}
+
+impl EventRecord {
+    #[must_use]
+    pub const fn new(headers: EventHeaders, payload: EventPayload) -> Self {
+        Self { headers, payload }
+    }
+}
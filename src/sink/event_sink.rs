@@ -0,0 +1,50 @@
+use crate::{Error, EventHeaders, EventPayload, Plausible};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Future returned by `EventSink::send`.
+pub type SendFuture<'a> = Pin<Box<dyn Future<Output = Result<(), Error>> + Send + 'a>>;
+
+/// Destination for pageview and custom events.
+///
+/// `Plausible` is one implementation. Code that records events can depend on a
+/// `DynEventSink` instead of the concrete client, so the same events can be sent elsewhere,
+/// e.g. to stdout during development or to memory in tests.
+pub trait EventSink: Send + Sync {
+    /// Records a pageview or custom event.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the event could not be recorded.
+    fn send(&self, headers: EventHeaders, payload: EventPayload) -> SendFuture<'_>;
+}
+
+/// Shared, type-erased `EventSink`.
+pub type DynEventSink = Arc<dyn EventSink>;
+
+impl EventSink for Plausible {
+    fn send(&self, headers: EventHeaders, payload: EventPayload) -> SendFuture<'_> {
+        Box::pin(async move { self.event(headers, payload).await.map(|_| ()) })
+    }
+}
+
+impl<S: EventSink + ?Sized> EventSink for Arc<S> {
+    fn send(&self, headers: EventHeaders, payload: EventPayload) -> SendFuture<'_> {
+        (**self).send(headers, payload)
+    }
+}
+
+impl<S: EventSink + ?Sized> EventSink for Box<S> {
+    fn send(&self, headers: EventHeaders, payload: EventPayload) -> SendFuture<'_> {
+        (**self).send(headers, payload)
+    }
+}
+
+impl Plausible {
+    /// Returns this client as a shared, type-erased `EventSink`.
+    #[must_use]
+    pub fn into_event_sink(self) -> DynEventSink {
+        Arc::new(self)
+    }
+}
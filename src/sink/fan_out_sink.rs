@@ -0,0 +1,46 @@
+use crate::{DynEventSink, Error, EventHeaders, EventPayload, EventSink, SendFuture};
+
+/// `EventSink` that sends every event to several sinks, e.g. Plausible and a `FileSink`.
+#[derive(Clone, Default)]
+pub struct FanOutSink {
+    sinks: Vec<DynEventSink>,
+}
+
+impl FanOutSink {
+    #[must_use]
+    pub const fn new(sinks: Vec<DynEventSink>) -> Self {
+        Self { sinks }
+    }
+
+    /// Also send every event to `sink`.
+    pub fn add(&mut self, sink: DynEventSink) -> &mut Self {
+        self.sinks.push(sink);
+        self
+    }
+}
+
+impl EventSink for FanOutSink {
+    /// Sends the event to every sink, even if some of them fail.
+    ///
+    /// Returns the first error, if any.
+    fn send(&self, headers: EventHeaders, payload: EventPayload) -> SendFuture<'_> {
+        Box::pin(async move {
+            let mut result: Result<(), Error> = Ok(());
+            for sink in &self.sinks {
+                let sent: Result<(), Error> = sink.send(headers.clone(), payload.clone()).await;
+                if result.is_ok() {
+                    result = sent;
+                }
+            }
+            result
+        })
+    }
+}
+
+impl std::fmt::Debug for FanOutSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FanOutSink")
+            .field("sinks", &self.sinks.len())
+            .finish()
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{Error, EventHeaders, EventPayload, EventRecord, EventSink, SendFuture};
+use std::path::Path;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+/// `EventSink` that appends every event to a file as a line of JSON (JSONL), e.g. for audits.
+#[derive(Debug)]
+pub struct FileSink {
+    file: Mutex<File>,
+}
+
+impl FileSink {
+    /// Open `path` for appending, creating it if it doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the file could not be opened.
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let file: File = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl EventSink for FileSink {
+    fn send(&self, headers: EventHeaders, payload: EventPayload) -> SendFuture<'_> {
+        Box::pin(async move {
+            let mut line: String = serde_json::to_string(&EventRecord::new(headers, payload))?;
+            line.push('\n');
+
+            let mut file = self.file.lock().await;
+            file.write_all(line.as_bytes()).await?;
+            file.flush().await?;
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,46 @@
+use crate::{EventHeaders, EventPayload, EventRecord, EventSink, SendFuture};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// `EventSink` that keeps every event in memory, e.g. for tests.
+///
+/// Clones share the same events.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySink {
+    events: Arc<Mutex<Vec<EventRecord>>>,
+}
+
+impl MemorySink {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns all events sent so far.
+    #[must_use]
+    pub fn events(&self) -> Vec<EventRecord> {
+        self.events
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Forgets all events sent so far.
+    pub fn clear(&self) {
+        self.events
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
+
+impl EventSink for MemorySink {
+    fn send(&self, headers: EventHeaders, payload: EventPayload) -> SendFuture<'_> {
+        Box::pin(async move {
+            self.events
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(EventRecord::new(headers, payload));
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,13 @@
+mod event_record;
+mod event_sink;
+mod fan_out_sink;
+mod file_sink;
+mod memory_sink;
+mod stdout_sink;
+
+pub use event_record::*;
+pub use event_sink::*;
+pub use fan_out_sink::*;
+pub use file_sink::*;
+pub use memory_sink::*;
+pub use stdout_sink::*;
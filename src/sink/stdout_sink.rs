@@ -0,0 +1,23 @@
+use crate::{EventHeaders, EventPayload, EventRecord, EventSink, SendFuture};
+use std::io::Write;
+
+/// `EventSink` that prints every event to stdout as a line of JSON.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdoutSink;
+
+impl StdoutSink {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl EventSink for StdoutSink {
+    fn send(&self, headers: EventHeaders, payload: EventPayload) -> SendFuture<'_> {
+        Box::pin(async move {
+            let line: String = serde_json::to_string(&EventRecord::new(headers, payload))?;
+            writeln!(std::io::stdout().lock(), "{line}")?;
+            Ok(())
+        })
+    }
+}
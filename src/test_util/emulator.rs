@@ -0,0 +1,407 @@
+use crate::hash::Fnv1a;
+use crate::test_util::{BreakdownRow, Interval, Metrics, Property, StatsQuery, TimeseriesPoint};
+use crate::{EventHeaders, EventPayload, EventSink, PAGEVIEW_EVENT, PropValue, SendFuture, Utm};
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, TimeDelta, Utc};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use url::Url;
+
+/// Source of visits without a referrer or `utm_source`.
+pub const DIRECT_SOURCE: &str = "Direct / None";
+
+/// A visit ends after this long without events from the visitor.
+const SESSION_TIMEOUT: TimeDelta = TimeDelta::minutes(30);
+
+/// In-process stand-in for Plausible's stats, computed from the events it received.
+///
+/// Like Plausible, visitors are told apart by hashing the site's domain, the visitor's IP address
+/// and User-Agent, and the day, so the same visitor counts once per day.
+/// Events of a visitor are grouped into visits, which end after 30 minutes without events.
+///
+/// Results won't match Plausible exactly, e.g. referrers aren't grouped into sources such as
+/// `Google`, but they are deterministic: the same events always give the same stats.
+///
+/// Clones share the same events.
+///
+/// ```rust
+/// use chrono::{TimeZone, Utc};
+/// use plausible_rs::test_util::{Emulator, Property, StatsQuery};
+/// use plausible_rs::{EventHeaders, EventPayload, PAGEVIEW_EVENT};
+///
+/// let emulator: Emulator = Emulator::new();
+/// for (ip, path) in [("10.0.0.1", "/"), ("10.0.0.1", "/blog"), ("10.0.0.2", "/")] {
+///     emulator.ingest_at(
+///         &EventHeaders::new(String::from("Mozilla/5.0"), ip.to_string()),
+///         &EventPayload::builder(
+///             String::from("example.com"),
+///             PAGEVIEW_EVENT.to_string(),
+///             format!("https://example.com{path}"),
+///         )
+///         .build(),
+///         Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap(),
+///     );
+/// }
+///
+/// let stats = emulator.aggregate(&StatsQuery::new("example.com"));
+/// assert_eq!((stats.visitors, stats.pageviews), (2, 3));
+///
+/// let pages = emulator.breakdown(&StatsQuery::new("example.com"), &Property::EventPage);
+/// assert_eq!(pages[0].value, "/");
+/// assert_eq!(pages[0].metrics.visitors, 2);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Emulator {
+    events: Arc<Mutex<Vec<Ingested>>>,
+}
+
+/// An event as stored by the emulator, for one site.
+#[derive(Debug, Clone)]
+struct Ingested {
+    timestamp: DateTime<Utc>,
+    domain: String,
+    visitor_id: u64,
+    name: String,
+    page: String,
+    source: String,
+    utm: Utm,
+    props: HashMap<String, String>,
+}
+
+/// Events of one visitor, without a gap longer than `SESSION_TIMEOUT`.
+struct Visit<'a> {
+    events: Vec<&'a Ingested>,
+}
+
+impl Visit<'_> {
+    fn entry(&self) -> &Ingested {
+        self.events[0]
+    }
+
+    fn entry_page(&self) -> &str {
+        self.events
+            .iter()
+            .find(|e| e.name == PAGEVIEW_EVENT)
+            .map_or(&self.entry().page, |e| &e.page)
+    }
+
+    fn pageviews(&self) -> u64 {
+        self.events
+            .iter()
+            .map(|e| u64::from(e.name == PAGEVIEW_EVENT))
+            .sum()
+    }
+
+    fn duration(&self) -> TimeDelta {
+        self.events[self.events.len() - 1].timestamp - self.entry().timestamp
+    }
+
+    /// Returns the value of `property` for `event`, which belongs to this visit.
+    fn property(&self, event: &Ingested, property: &Property) -> Option<String> {
+        match property {
+            Property::EventName => Some(event.name.clone()),
+            Property::EventPage => Some(event.page.clone()),
+            Property::VisitEntryPage => Some(self.entry_page().to_string()),
+            Property::VisitSource => Some(self.entry().source.clone()),
+            Property::VisitUtmSource => self.entry().utm.source.clone(),
+            Property::VisitUtmMedium => self.entry().utm.medium.clone(),
+            Property::VisitUtmCampaign => self.entry().utm.campaign.clone(),
+            Property::EventProp(key) => event.props.get(key).cloned(),
+        }
+    }
+
+    fn matches(&self, event: &Ingested, filters: &[(Property, String)]) -> bool {
+        filters
+            .iter()
+            .all(|(property, value)| self.property(event, property).as_ref() == Some(value))
+    }
+}
+
+impl Emulator {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn events(&self) -> MutexGuard<'_, Vec<Ingested>> {
+        self.events.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Records an event as received now.
+    pub fn ingest(&self, headers: &EventHeaders, payload: &EventPayload) {
+        self.ingest_at(headers, payload, Utc::now());
+    }
+
+    /// Records an event as received at `timestamp`.
+    ///
+    /// Events for several domains are recorded for each of the sites.
+    pub fn ingest_at(
+        &self,
+        headers: &EventHeaders,
+        payload: &EventPayload,
+        timestamp: DateTime<Utc>,
+    ) {
+        let url: Option<Url> = Url::parse(&payload.url).ok();
+        let page: String = url.as_ref().map_or_else(
+            || payload.url.clone(),
+            |url| match url.fragment().filter(|_| payload.hash_mode) {
+                Some(fragment) => format!("{}#{fragment}", url.path()),
+                None => url.path().to_string(),
+            },
+        );
+        let utm: Utm = Utm::from_url(&payload.url).unwrap_or_default();
+        let props: HashMap<String, String> = payload
+            .props
+            .iter()
+            .flatten()
+            .map(|(key, value)| (key.clone(), prop_to_string(value)))
+            .collect();
+
+        let mut events = self.events();
+        for domain in payload.domains() {
+            events.push(Ingested {
+                timestamp,
+                domain: domain.to_string(),
+                visitor_id: visitor_id(domain, headers, timestamp.date_naive()),
+                name: payload.name.clone(),
+                page: page.clone(),
+                source: source(domain, &utm, payload.referrer.as_deref()),
+                utm: utm.clone(),
+                props: props.clone(),
+            });
+        }
+    }
+
+    /// Forgets every event received so far.
+    pub fn clear(&self) {
+        self.events().clear();
+    }
+
+    /// Returns the metrics of the events matching `query`.
+    #[must_use]
+    pub fn aggregate(&self, query: &StatsQuery) -> Metrics {
+        let events = self.events();
+        let events: Vec<&Ingested> = site_events(&events, query);
+        metrics(&events, &query.filters)
+    }
+
+    /// Returns the metrics of the events matching `query`, by day or month.
+    ///
+    /// Buckets without events are included, with all metrics set to zero.
+    /// For `Period::All`, buckets range from the first to the last event.
+    #[must_use]
+    pub fn timeseries(&self, query: &StatsQuery, interval: Interval) -> Vec<TimeseriesPoint> {
+        let events = self.events();
+        let events: Vec<&Ingested> = site_events(&events, query);
+        let bucket = |date: NaiveDate| match interval {
+            Interval::Date => date,
+            Interval::Month => date.with_day(1).unwrap_or(date),
+        };
+
+        // without bounds, range from the first to the last event
+        let bounds: Option<(NaiveDate, NaiveDate)> = query.period.bounds().or_else(|| {
+            let first: &Ingested = events.first()?;
+            let last: &Ingested = events.last()?;
+            Some((first.timestamp.date_naive(), last.timestamp.date_naive()))
+        });
+        let Some((from, to)) = bounds else {
+            return Vec::new();
+        };
+
+        let mut points: Vec<TimeseriesPoint> = Vec::new();
+        let mut date: NaiveDate = bucket(from);
+        while date <= to {
+            let bucket_events: Vec<&Ingested> = events
+                .iter()
+                .copied()
+                .filter(|e| bucket(e.timestamp.date_naive()) == date)
+                .collect();
+            points.push(TimeseriesPoint {
+                date,
+                metrics: metrics(&bucket_events, &query.filters),
+            });
+            date = match interval {
+                Interval::Date => date + Days::new(1),
+                Interval::Month => date + Months::new(1),
+            };
+        }
+        points
+    }
+
+    /// Returns the metrics of the events matching `query`, by value of `property`.
+    ///
+    /// Rows are sorted by visitors, most first, then by value.
+    /// Events without a value for `property` are left out.
+    #[must_use]
+    pub fn breakdown(&self, query: &StatsQuery, property: &Property) -> Vec<BreakdownRow> {
+        let events = self.events();
+        let events: Vec<&Ingested> = site_events(&events, query);
+
+        let mut values: BTreeSet<String> = BTreeSet::new();
+        for visit in visits(&events) {
+            for event in &visit.events {
+                if visit.matches(event, &query.filters) {
+                    values.extend(visit.property(event, property));
+                }
+            }
+        }
+
+        let mut rows: Vec<BreakdownRow> = values
+            .into_iter()
+            .map(|value| {
+                let mut filters: Vec<(Property, String)> = query.filters.clone();
+                filters.push((property.clone(), value.clone()));
+                BreakdownRow {
+                    metrics: metrics(&events, &filters),
+                    value,
+                }
+            })
+            .collect();
+        rows.sort_by(|a, b| {
+            b.metrics
+                .visitors
+                .cmp(&a.metrics.visitors)
+                .then_with(|| a.value.cmp(&b.value))
+        });
+        rows
+    }
+
+    /// Returns the number of visitors in the last 5 minutes, like the realtime API.
+    #[must_use]
+    pub fn realtime_visitors(&self, site_id: &str) -> u64 {
+        let since: DateTime<Utc> = Utc::now() - TimeDelta::minutes(5);
+        let visitors: HashSet<u64> = self
+            .events()
+            .iter()
+            .filter(|e| e.domain == site_id && e.timestamp >= since)
+            .map(|e| e.visitor_id)
+            .collect();
+        visitors.len() as u64
+    }
+}
+
+impl EventSink for Emulator {
+    fn send(&self, headers: EventHeaders, payload: EventPayload) -> SendFuture<'_> {
+        self.ingest(&headers, &payload);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Returns the events of the queried site within the queried period, oldest first.
+fn site_events<'a>(events: &'a [Ingested], query: &StatsQuery) -> Vec<&'a Ingested> {
+    let mut events: Vec<&Ingested> = events
+        .iter()
+        .filter(|e| e.domain == query.site_id && query.period.contains(e.timestamp.date_naive()))
+        .collect();
+    events.sort_by_key(|e| e.timestamp);
+    events
+}
+
+/// Groups `events`, oldest first, into visits.
+fn visits<'a>(events: &[&'a Ingested]) -> Vec<Visit<'a>> {
+    let mut visits: Vec<Visit<'a>> = Vec::new();
+    let mut current: HashMap<u64, usize> = HashMap::new();
+    for event in events {
+        let visit: Option<usize> = current.get(&event.visitor_id).copied().filter(|&i| {
+            let last: &Ingested = visits[i].events[visits[i].events.len() - 1];
+            event.timestamp - last.timestamp <= SESSION_TIMEOUT
+        });
+        if let Some(i) = visit {
+            visits[i].events.push(event);
+        } else {
+            current.insert(event.visitor_id, visits.len());
+            visits.push(Visit {
+                events: vec![event],
+            });
+        }
+    }
+    visits
+}
+
+/// Computes the metrics of the events matching `filters`, and of the visits they belong to.
+fn metrics(events: &[&Ingested], filters: &[(Property, String)]) -> Metrics {
+    let mut metrics: Metrics = Metrics::default();
+    let mut visitors: HashSet<u64> = HashSet::new();
+    let (mut bounces, mut duration, mut visit_pageviews) = (0_u64, 0_u64, 0_u64);
+
+    for visit in visits(events) {
+        let mut matched: bool = false;
+        for event in visit.events.iter().filter(|e| visit.matches(e, filters)) {
+            matched = true;
+            metrics.events += 1;
+            metrics.pageviews += u64::from(event.name == PAGEVIEW_EVENT);
+        }
+        if !matched {
+            continue;
+        }
+
+        visitors.insert(visit.entry().visitor_id);
+        metrics.visits += 1;
+        bounces += u64::from(visit.events.len() == 1);
+        duration += u64::try_from(visit.duration().num_seconds()).unwrap_or_default();
+        visit_pageviews += visit.pageviews();
+    }
+
+    metrics.visitors = visitors.len() as u64;
+    if metrics.visits > 0 {
+        metrics.bounce_rate = div_round(bounces * 100, metrics.visits);
+        metrics.visit_duration = div_round(duration, metrics.visits);
+        metrics.views_per_visit = hundredths(div_round(visit_pageviews * 100, metrics.visits));
+    }
+    metrics
+}
+
+/// Divides, rounding half up.
+const fn div_round(dividend: u64, divisor: u64) -> u64 {
+    (dividend + divisor / 2) / divisor
+}
+
+#[expect(
+    clippy::cast_precision_loss,
+    reason = "test stats are far below the 2^52 where precision is lost"
+)]
+fn hundredths(value: u64) -> f64 {
+    value as f64 / 100.0
+}
+
+/// Identifies a visitor the way Plausible does, from a hash that changes every day.
+fn visitor_id(domain: &str, headers: &EventHeaders, date: NaiveDate) -> u64 {
+    // Plausible uses the first address of X-Forwarded-For
+    let ip: &str = headers
+        .x_forwarded_for
+        .split(',')
+        .next()
+        .unwrap_or_default()
+        .trim();
+
+    let mut hasher = Fnv1a::new();
+    hasher.write_field(date.to_string().as_bytes());
+    hasher.write_field(domain.as_bytes());
+    hasher.write_field(ip.as_bytes());
+    hasher.write_field(headers.user_agent.as_bytes());
+    hasher.finish()
+}
+
+/// Returns the source of a visit, from `utm_source` or the referrer's domain.
+///
+/// Referrals from the site itself are ignored.
+fn source(domain: &str, utm: &Utm, referrer: Option<&str>) -> String {
+    if let Some(source) = &utm.source {
+        return source.clone();
+    }
+
+    referrer
+        .and_then(|referrer| Url::parse(referrer).ok())
+        .and_then(|referrer| referrer.host_str().map(ToString::to_string))
+        .map(|host| host.trim_start_matches("www.").to_string())
+        .filter(|host| host != domain)
+        .unwrap_or_else(|| DIRECT_SOURCE.to_string())
+}
+
+/// Returns a custom property as Plausible stores it, as a string.
+fn prop_to_string(value: &PropValue) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(s)) => s,
+        Ok(value) => value.to_string(),
+        Err(_) => String::new(),
+    }
+}
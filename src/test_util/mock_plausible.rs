@@ -0,0 +1,321 @@
+use crate::{
+    DROPPED_HEADER, Error, EventHeaders, EventOutcome, EventPayload, EventRecord, EventResponse,
+    EventSink, HealthResponse, PrivacySignals, PropValue, SendFuture,
+};
+use bytes::Bytes;
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, HeaderValue};
+use std::collections::VecDeque;
+use std::pin::pin;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Scripted response of `MockPlausible`.
+#[derive(Debug, Clone)]
+pub enum MockResponse {
+    /// Respond like Plausible does to a recorded event, or a healthy API.
+    Ok,
+
+    /// Respond like Plausible does to an event it ignored.
+    ///
+    /// Only applies to `MockPlausible::event`.
+    Dropped,
+
+    /// Fail with `Error::RequestFailed`.
+    Failed {
+        status_code: StatusCode,
+        bytes: Bytes,
+    },
+}
+
+impl MockResponse {
+    /// Fail with `Error::RequestFailed` and an empty body.
+    #[must_use]
+    pub const fn failed(status_code: StatusCode) -> Self {
+        Self::Failed {
+            status_code,
+            bytes: Bytes::new(),
+        }
+    }
+}
+
+/// In-memory stand-in for `Plausible`, which records every event instead of sending it.
+///
+/// Clones share the same recorded events and scripted responses.
+///
+/// ```rust
+/// use plausible_rs::test_util::{MockPlausible, MockResponse};
+/// use plausible_rs::{EventHeaders, EventPayload, PropValue};
+/// use reqwest::StatusCode;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let plausible: MockPlausible = MockPlausible::new();
+/// let mut payload: EventPayload = EventPayload::builder(
+///     String::from("example.com"),
+///     String::from("Signup"),
+///     String::from("https://example.com/signup"),
+/// )
+/// .build();
+/// payload.props = Some([(String::from("plan"), PropValue::from(String::from("pro")))].into());
+///
+/// plausible
+///     .event(EventHeaders::new(String::from("Mozilla/5.0"), String::from("127.0.0.1")), payload)
+///     .await
+///     .unwrap();
+/// plausible.assert_event_with_prop("Signup", "plan", String::from("pro"));
+///
+/// // the next event fails
+/// plausible.respond_to_next_event(MockResponse::failed(StatusCode::BAD_REQUEST));
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MockPlausible {
+    state: Arc<Mutex<State>>,
+    received: Arc<Notify>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    events: Vec<EventRecord>,
+    event_responses: VecDeque<MockResponse>,
+    health_responses: VecDeque<MockResponse>,
+}
+
+impl MockPlausible {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Records a pageview or custom event, like `Plausible::event`.
+    ///
+    /// Every event is recorded, including those that are scripted to fail.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the next response was scripted to fail.
+    #[expect(clippy::unused_async, reason = "mirrors the signature of `Plausible`")]
+    pub async fn event(
+        &self,
+        headers: EventHeaders,
+        payload: EventPayload,
+    ) -> Result<EventOutcome, Error> {
+        let privacy_signals: PrivacySignals = headers.privacy_signals;
+        let response: Option<MockResponse> = {
+            let mut state = self.state();
+            state.events.push(EventRecord::new(headers, payload));
+            state.event_responses.pop_front()
+        };
+        self.received.notify_waiters();
+
+        let mut response_headers: HeaderMap = HeaderMap::new();
+        match response.unwrap_or(MockResponse::Ok) {
+            MockResponse::Ok => Ok(EventOutcome::Accepted(EventResponse {
+                status_code: StatusCode::ACCEPTED,
+                headers: response_headers,
+                bytes: Bytes::from_static(b"ok"),
+                anonymized: false,
+                privacy_signals,
+            })),
+            MockResponse::Dropped => {
+                response_headers.insert(DROPPED_HEADER, HeaderValue::from_static("1"));
+                Ok(EventOutcome::Dropped(EventResponse {
+                    status_code: StatusCode::ACCEPTED,
+                    headers: response_headers,
+                    bytes: Bytes::from_static(b"ok"),
+                    anonymized: false,
+                    privacy_signals,
+                }))
+            }
+            MockResponse::Failed { status_code, bytes } => {
+                Err(Error::RequestFailed { bytes, status_code })
+            }
+        }
+    }
+
+    /// Monitors the status of the mocked API, like `Plausible::health`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the next response was scripted to fail.
+    #[expect(clippy::unused_async, reason = "mirrors the signature of `Plausible`")]
+    pub async fn health(&self) -> Result<HealthResponse, Error> {
+        match self.state().health_responses.pop_front() {
+            None | Some(MockResponse::Ok | MockResponse::Dropped) => Ok(HealthResponse {
+                clickhouse: String::from("ok"),
+                postgres: String::from("ok"),
+            }),
+            Some(MockResponse::Failed { status_code, bytes }) => {
+                Err(Error::RequestFailed { bytes, status_code })
+            }
+        }
+    }
+
+    /// Scripts the response to the next call to `Self::event`.
+    ///
+    /// Responses are used in the order they were scripted. Once they are used up, events are
+    /// accepted.
+    pub fn respond_to_next_event(&self, response: MockResponse) -> &Self {
+        self.state().event_responses.push_back(response);
+        self
+    }
+
+    /// Scripts the response to the next call to `Self::health`.
+    ///
+    /// Responses are used in the order they were scripted. Once they are used up, the API is
+    /// healthy.
+    pub fn respond_to_next_health(&self, response: MockResponse) -> &Self {
+        self.state().health_responses.push_back(response);
+        self
+    }
+
+    /// Returns every event received so far.
+    #[must_use]
+    pub fn events(&self) -> Vec<EventRecord> {
+        self.state().events.clone()
+    }
+
+    /// Waits until at least `count` events were received, and returns every event received so far.
+    ///
+    /// Useful when events are sent in the background, e.g. with `Plausible::event_detached`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if fewer than `count` events were received within `timeout`.
+    pub async fn wait_for_events(&self, count: usize, timeout: Duration) -> Vec<EventRecord> {
+        let wait = async {
+            loop {
+                // listen before checking, so that an event received in between isn't missed
+                let mut received = pin!(self.received.notified());
+                received.as_mut().enable();
+                if self.state().events.len() >= count {
+                    return;
+                }
+                received.await;
+            }
+        };
+        let waited: bool = tokio::time::timeout(timeout, wait).await.is_ok();
+        assert!(
+            waited,
+            "expected {count} events within {timeout:?}, received {:?}",
+            self.event_names()
+        );
+        self.events()
+    }
+
+    /// Returns every event named `name` received so far.
+    #[must_use]
+    pub fn events_named(&self, name: &str) -> Vec<EventRecord> {
+        self.state()
+            .events
+            .iter()
+            .filter(|e| e.payload.name == name)
+            .cloned()
+            .collect()
+    }
+
+    /// Forgets every event received so far.
+    pub fn clear(&self) {
+        self.state().events.clear();
+    }
+
+    /// Asserts that no event was received.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if any event was received.
+    pub fn assert_no_events(&self) {
+        let names: Vec<String> = self.event_names();
+        assert!(names.is_empty(), "expected no events, received {names:?}");
+    }
+
+    /// Asserts that exactly `count` events were received.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if a different number of events was received.
+    pub fn assert_event_count(&self, count: usize) {
+        let names: Vec<String> = self.event_names();
+        assert_eq!(
+            names.len(),
+            count,
+            "expected {count} events, received {names:?}"
+        );
+    }
+
+    /// Asserts that an event named `name` was received, and returns the first one.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if no event named `name` was received.
+    #[expect(clippy::must_use_candidate, reason = "assertions are often used alone")]
+    pub fn assert_event_received(&self, name: &str) -> EventRecord {
+        self.events_named(name)
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| {
+                panic!(
+                    "expected an event named {name:?}, received {:?}",
+                    self.event_names()
+                )
+            })
+    }
+
+    /// Asserts that an event named `name` with the custom property `key` set to `value` was
+    /// received, and returns the first one.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if no such event was received.
+    pub fn assert_event_with_prop(
+        &self,
+        name: &str,
+        key: &str,
+        value: impl Into<PropValue>,
+    ) -> EventRecord {
+        let value: PropValue = value.into();
+        let events: Vec<EventRecord> = self.events_named(name);
+        events
+            .iter()
+            .find(|e| {
+                e.payload
+                    .props
+                    .as_ref()
+                    .and_then(|props| props.get(key))
+                    .is_some_and(|v| prop_eq(v, &value))
+            })
+            .cloned()
+            .unwrap_or_else(|| {
+                let props: Vec<_> = events.iter().map(|e| &e.payload.props).collect();
+                panic!(
+                    "expected an event named {name:?} with prop {key:?} = {value:?}, \
+                     received these props for {name:?}: {props:?}"
+                )
+            })
+    }
+
+    fn event_names(&self) -> Vec<String> {
+        self.state()
+            .events
+            .iter()
+            .map(|e| e.payload.name.clone())
+            .collect()
+    }
+}
+
+impl EventSink for MockPlausible {
+    fn send(&self, headers: EventHeaders, payload: EventPayload) -> SendFuture<'_> {
+        Box::pin(async move { self.event(headers, payload).await.map(|_| ()) })
+    }
+}
+
+/// Compares prop values as they are sent to Plausible, so e.g. `1_u8` equals `1_i64`.
+fn prop_eq(a: &PropValue, b: &PropValue) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
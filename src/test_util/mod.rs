@@ -0,0 +1,12 @@
+//! Test doubles for code that records events, enabled by the `test-util` feature.
+
+mod emulator;
+mod mock_plausible;
+mod plausible_server;
+mod stats_api;
+mod stats_query;
+
+pub use emulator::*;
+pub use mock_plausible::*;
+pub use plausible_server::*;
+pub use stats_query::*;
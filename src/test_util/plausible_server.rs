@@ -0,0 +1,382 @@
+use crate::test_util::{Emulator, MockPlausible, stats_api};
+use crate::{
+    DROPPED_HEADER, DynEventSink, Error, EventHeaders, EventOutcome, EventPayload, Plausible,
+};
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::header::{CONTENT_TYPE, HeaderValue};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde::Serialize;
+use serde_json::json;
+use std::convert::Infallible;
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// Local stand-in for the Plausible Analytics API, for hermetic integration tests.
+///
+/// Serves `POST /api/event` and `GET /api/health` over real HTTP, so requests are serialized,
+/// sent and parsed exactly like they are against Plausible.
+/// Received events are recorded by, and responses scripted with, a `MockPlausible`.
+///
+/// Accepted events are also ingested by an `Emulator`, which answers the aggregate, timeseries,
+/// breakdown and realtime visitors endpoints of the Stats API under `/api/v1/stats/`.
+/// The `Authorization` header is not checked.
+///
+/// The server stops when dropped.
+///
+/// ```rust
+/// use plausible_rs::test_util::{MockResponse, PlausibleServer};
+/// use plausible_rs::{EventHeaders, EventPayload, PAGEVIEW_EVENT, Plausible};
+/// use reqwest::StatusCode;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+/// let plausible: Plausible = server.client();
+///
+/// plausible
+///     .event(
+///         EventHeaders::new(String::from("Mozilla/5.0"), String::from("127.0.0.1")),
+///         EventPayload::builder(
+///             String::from("example.com"),
+///             PAGEVIEW_EVENT.to_string(),
+///             String::from("https://example.com/"),
+///         )
+///         .build(),
+///     )
+///     .await
+///     .unwrap();
+/// server.mock().assert_event_received(PAGEVIEW_EVENT);
+///
+/// server
+///     .mock()
+///     .respond_to_next_health(MockResponse::failed(StatusCode::SERVICE_UNAVAILABLE));
+/// assert!(plausible.health().await.is_err());
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct PlausibleServer {
+    addr: SocketAddr,
+    mock: MockPlausible,
+    emulator: Emulator,
+    task: JoinHandle<()>,
+}
+
+/// Local Plausible stand-in configuration.
+///
+/// This is a Builder for `PlausibleServer`.
+#[derive(Clone)]
+pub struct PlausibleServerBuilder {
+    /// Address to listen on. Defaults to a random port on localhost.
+    pub addr: SocketAddr,
+
+    /// Records received events and scripts responses.
+    pub mock: MockPlausible,
+
+    /// Computes the stats served by the Stats API endpoints from accepted events.
+    pub emulator: Emulator,
+
+    /// Also receives every event that is accepted, if set.
+    pub sink: Option<DynEventSink>,
+}
+
+impl PlausibleServer {
+    /// Starts a server on a random port on localhost.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the port could not be bound.
+    pub async fn start() -> io::Result<Self> {
+        PlausibleServerBuilder::new().start().await
+    }
+
+    #[must_use]
+    pub fn builder() -> PlausibleServerBuilder {
+        PlausibleServerBuilder::new()
+    }
+
+    /// Returns the address the server listens on.
+    #[must_use]
+    pub const fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the base URL of the server, to pass to `PlausibleBuilder::base_url`.
+    #[must_use]
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Returns a client that sends requests to this server.
+    #[must_use]
+    pub fn client(&self) -> Plausible {
+        Plausible::builder().base_url(self.base_url()).build()
+    }
+
+    /// Returns the mock recording received events, to script responses and make assertions.
+    #[must_use]
+    pub const fn mock(&self) -> &MockPlausible {
+        &self.mock
+    }
+
+    /// Returns the emulator computing the stats served by the Stats API endpoints.
+    #[must_use]
+    pub const fn emulator(&self) -> &Emulator {
+        &self.emulator
+    }
+
+    /// Serves requests until the server task ends, e.g. from a binary.
+    pub async fn wait(mut self) {
+        let _ = (&mut self.task).await;
+    }
+}
+
+impl Drop for PlausibleServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl PlausibleServerBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            addr: SocketAddr::from((Ipv4Addr::LOCALHOST, 0)),
+            mock: MockPlausible::new(),
+            emulator: Emulator::new(),
+            sink: None,
+        }
+    }
+
+    pub const fn addr(&mut self, addr: SocketAddr) -> &mut Self {
+        self.addr = addr;
+        self
+    }
+
+    pub fn mock(&mut self, mock: MockPlausible) -> &mut Self {
+        self.mock = mock;
+        self
+    }
+
+    pub fn emulator(&mut self, emulator: Emulator) -> &mut Self {
+        self.emulator = emulator;
+        self
+    }
+
+    pub fn sink(&mut self, sink: DynEventSink) -> &mut Self {
+        self.sink = Some(sink);
+        self
+    }
+
+    /// Binds the address and starts serving requests on the current Tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the address could not be bound.
+    pub async fn start(&self) -> io::Result<PlausibleServer> {
+        let listener: TcpListener = TcpListener::bind(self.addr).await?;
+        let addr: SocketAddr = listener.local_addr()?;
+        let handler: Arc<Handler> = Arc::new(Handler {
+            mock: self.mock.clone(),
+            emulator: self.emulator.clone(),
+            sink: self.sink.clone(),
+        });
+        let task: JoinHandle<()> = tokio::spawn(serve(listener, handler));
+
+        Ok(PlausibleServer {
+            addr,
+            mock: self.mock.clone(),
+            emulator: self.emulator.clone(),
+            task,
+        })
+    }
+}
+
+impl Default for PlausibleServerBuilder {
+    /// Defaults to `Self::new()`.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for PlausibleServerBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PlausibleServerBuilder")
+            .field("addr", &self.addr)
+            .field("mock", &self.mock)
+            .field("emulator", &self.emulator)
+            .field("sink", &self.sink.as_ref().map(|_| "DynEventSink"))
+            .finish()
+    }
+}
+
+type HttpResponse = Response<Full<Bytes>>;
+
+struct Handler {
+    mock: MockPlausible,
+    emulator: Emulator,
+    sink: Option<DynEventSink>,
+}
+
+async fn serve(listener: TcpListener, handler: Arc<Handler>) {
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                log::warn!("local Plausible failed to accept a connection: {e}");
+                continue;
+            }
+        };
+
+        let handler: Arc<Handler> = handler.clone();
+        tokio::spawn(async move {
+            let service = service_fn(|request: Request<Incoming>| {
+                let handler: Arc<Handler> = handler.clone();
+                async move { Ok::<_, Infallible>(handler.handle(request, peer).await) }
+            });
+            if let Err(e) = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                log::debug!("local Plausible connection from {peer} failed: {e}");
+            }
+        });
+    }
+}
+
+impl Handler {
+    async fn handle(&self, request: Request<Incoming>, peer: SocketAddr) -> HttpResponse {
+        let stats_endpoint: Option<&str> = request.uri().path().strip_prefix("/api/v1/stats/");
+        match (request.method(), request.uri().path(), stats_endpoint) {
+            (&Method::POST, "/api/event", _) => self.event(request, peer).await,
+            (&Method::GET, "/api/health", _) => self.health().await,
+            (&Method::GET, _, Some(endpoint)) => {
+                match stats_api::respond(
+                    &self.emulator,
+                    endpoint,
+                    request.uri().query().unwrap_or_default(),
+                ) {
+                    Some(Ok(stats)) => json_response(StatusCode::OK, &stats),
+                    Some(Err(e)) => {
+                        json_response(StatusCode::BAD_REQUEST, &json!({ "error": e.to_string() }))
+                    }
+                    None => text(StatusCode::NOT_FOUND, "not found"),
+                }
+            }
+            _ => text(StatusCode::NOT_FOUND, "not found"),
+        }
+    }
+
+    /// Records an event like `POST /api/event`.
+    async fn event(&self, request: Request<Incoming>, peer: SocketAddr) -> HttpResponse {
+        let header = |name: &str| {
+            request
+                .headers()
+                .get(name)
+                .and_then(|value: &HeaderValue| value.to_str().ok())
+                .map(ToString::to_string)
+        };
+
+        // like Plausible, fall back to the IP address of the connection
+        let headers: EventHeaders = EventHeaders::new(
+            header("user-agent").unwrap_or_default(),
+            header("x-forwarded-for").unwrap_or_else(|| peer.ip().to_string()),
+        );
+
+        let body: Bytes = match request.into_body().collect().await {
+            Ok(body) => body.to_bytes(),
+            Err(e) => return text(StatusCode::BAD_REQUEST, &e.to_string()),
+        };
+        let payload: EventPayload = match serde_json::from_slice(&body) {
+            Ok(payload) => payload,
+            Err(e) => {
+                return json_response(
+                    StatusCode::BAD_REQUEST,
+                    &json!({ "errors": { "request": [e.to_string()] } }),
+                );
+            }
+        };
+        if let Some(errors) = validate(&payload) {
+            return json_response(StatusCode::BAD_REQUEST, &errors);
+        }
+
+        match self.mock.event(headers.clone(), payload.clone()).await {
+            Ok(outcome) => {
+                if matches!(outcome, EventOutcome::Accepted(_)) {
+                    self.emulator.ingest(&headers, &payload);
+                    if let Some(sink) = &self.sink {
+                        if let Err(e) = sink.send(headers, payload).await {
+                            log::warn!("local Plausible failed to forward an event: {e}");
+                        }
+                    }
+                }
+
+                let mut response: HttpResponse = text(StatusCode::ACCEPTED, "ok");
+                if matches!(outcome, EventOutcome::Dropped(_)) {
+                    response
+                        .headers_mut()
+                        .insert(DROPPED_HEADER, HeaderValue::from_static("1"));
+                }
+                response
+            }
+            Err(e) => error_response(e),
+        }
+    }
+
+    /// Reports health like `GET /api/health`.
+    async fn health(&self) -> HttpResponse {
+        match self.mock.health().await {
+            Ok(health) => json_response(StatusCode::OK, &health),
+            Err(e) => error_response(e),
+        }
+    }
+}
+
+/// Returns the errors Plausible responds with when required fields are missing.
+fn validate(payload: &EventPayload) -> Option<serde_json::Value> {
+    let mut errors = serde_json::Map::new();
+    for (field, value) in [
+        ("domain", &payload.domain),
+        ("name", &payload.name),
+        ("url", &payload.url),
+    ] {
+        if value.is_empty() {
+            errors.insert(field.to_string(), json!(["can't be blank"]));
+        }
+    }
+    (!errors.is_empty()).then(|| json!({ "errors": errors }))
+}
+
+fn error_response(error: Error) -> HttpResponse {
+    match error {
+        Error::RequestFailed { bytes, status_code } => response(status_code, "text/plain", bytes),
+        e => text(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+fn text(status_code: StatusCode, body: &str) -> HttpResponse {
+    response(status_code, "text/plain", Bytes::from(body.to_string()))
+}
+
+fn json_response(status_code: StatusCode, body: &impl Serialize) -> HttpResponse {
+    match serde_json::to_vec(body) {
+        Ok(body) => response(status_code, "application/json", Bytes::from(body)),
+        Err(e) => text(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+fn response(status_code: StatusCode, content_type: &'static str, body: Bytes) -> HttpResponse {
+    let mut response: HttpResponse = Response::new(Full::new(body));
+    *response.status_mut() = status_code;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+    response
+}
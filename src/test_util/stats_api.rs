@@ -0,0 +1,100 @@
+use crate::test_util::{
+    Emulator, Interval, InvalidStatsQuery, Metric, Metrics, Period, Property, StatsQuery,
+};
+use chrono::{NaiveDate, Utc};
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+use url::form_urlencoded;
+
+/// Default number of rows returned by the breakdown endpoint.
+const DEFAULT_LIMIT: usize = 100;
+
+/// Answers a `GET /api/v1/stats/<endpoint>` request of the Stats API from `emulator`.
+///
+/// Returns `None` if `endpoint` is unknown.
+///
+/// See: <https://plausible.io/docs/stats-api>
+pub(super) fn respond(
+    emulator: &Emulator,
+    endpoint: &str,
+    query: &str,
+) -> Option<Result<Value, InvalidStatsQuery>> {
+    let params: HashMap<String, String> = form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect();
+    let param = |name: &str| params.get(name).map(String::as_str);
+
+    let result = match endpoint {
+        "realtime/visitors" => {
+            site_id(param("site_id")).map(|site_id| json!(emulator.realtime_visitors(site_id)))
+        }
+        "aggregate" => stats_query(&param).map(|(query, metrics)| {
+            let stats: Metrics = emulator.aggregate(&query);
+            let results: Map<String, Value> = metrics
+                .into_iter()
+                .map(|metric| (metric.to_string(), json!({ "value": stats.value(metric) })))
+                .collect();
+            json!({ "results": results })
+        }),
+        "timeseries" => stats_query(&param).and_then(|(query, metrics)| {
+            let interval: Interval = param("interval").map_or(Ok(Interval::Date), str::parse)?;
+            let results: Vec<Value> = emulator
+                .timeseries(&query, interval)
+                .into_iter()
+                .map(|point| {
+                    let mut row: Map<String, Value> = row(&point.metrics, &metrics);
+                    row.insert(String::from("date"), json!(point.date.to_string()));
+                    Value::Object(row)
+                })
+                .collect();
+            Ok(json!({ "results": results }))
+        }),
+        "breakdown" => stats_query(&param).and_then(|(query, metrics)| {
+            let property: Property = param("property")
+                .ok_or_else(|| InvalidStatsQuery(String::from("`property` is required")))?
+                .parse()?;
+            let limit: usize = param("limit").map_or(Ok(DEFAULT_LIMIT), |limit| {
+                limit
+                    .parse()
+                    .map_err(|_| InvalidStatsQuery(format!("invalid limit {limit:?}")))
+            })?;
+            let results: Vec<Value> = emulator
+                .breakdown(&query, &property)
+                .into_iter()
+                .take(limit)
+                .map(|breakdown| {
+                    let mut row: Map<String, Value> = row(&breakdown.metrics, &metrics);
+                    row.insert(property.key().to_string(), json!(breakdown.value));
+                    Value::Object(row)
+                })
+                .collect();
+            Ok(json!({ "results": results }))
+        }),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Parses the parameters shared by the aggregate, timeseries and breakdown endpoints.
+fn stats_query<'a>(
+    param: &impl Fn(&str) -> Option<&'a str>,
+) -> Result<(StatsQuery, Vec<Metric>), InvalidStatsQuery> {
+    let today: NaiveDate = Utc::now().date_naive();
+    let mut query: StatsQuery = StatsQuery::new(site_id(param("site_id"))?);
+    query.period = Period::parse(param("period").unwrap_or("30d"), param("date"), today)?;
+    query.filters = StatsQuery::parse_filters(param("filters").unwrap_or_default())?;
+
+    let metrics: Vec<Metric> = Metric::parse_list(param("metrics").unwrap_or("visitors"))?;
+    Ok((query, metrics))
+}
+
+fn site_id(site_id: Option<&str>) -> Result<&str, InvalidStatsQuery> {
+    site_id.ok_or_else(|| InvalidStatsQuery(String::from("`site_id` is required")))
+}
+
+fn row(stats: &Metrics, metrics: &[Metric]) -> Map<String, Value> {
+    metrics
+        .iter()
+        .map(|metric| (metric.to_string(), stats.value(*metric)))
+        .collect()
+}
@@ -0,0 +1,384 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+use serde_json::Value;
+use std::str::FromStr;
+use std::{error, fmt};
+
+/// A Stats API query had an unknown metric, property, period or interval, or an invalid date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidStatsQuery(pub String);
+
+impl error::Error for InvalidStatsQuery {}
+
+impl fmt::Display for InvalidStatsQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid stats query: {}", self.0)
+    }
+}
+
+/// Metric computed by the Stats API.
+///
+/// See: <https://plausible.io/docs/stats-api#metrics>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Metric {
+    /// Number of unique visitors.
+    Visitors,
+
+    /// Number of visits, also known as sessions.
+    Visits,
+
+    /// Number of pageview events.
+    Pageviews,
+
+    /// Number of pageview and custom events.
+    Events,
+
+    /// Percentage of visits with a single event, rounded to a whole number.
+    BounceRate,
+
+    /// Average duration of a visit in seconds, rounded to a whole number.
+    VisitDuration,
+
+    /// Average number of pageviews per visit, rounded to two decimals.
+    ViewsPerVisit,
+}
+
+impl Metric {
+    pub const ALL: &[Self] = &[
+        Self::Visitors,
+        Self::Visits,
+        Self::Pageviews,
+        Self::Events,
+        Self::BounceRate,
+        Self::VisitDuration,
+        Self::ViewsPerVisit,
+    ];
+
+    /// Returns the name of the metric in the Stats API, e.g. `bounce_rate`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Visitors => "visitors",
+            Self::Visits => "visits",
+            Self::Pageviews => "pageviews",
+            Self::Events => "events",
+            Self::BounceRate => "bounce_rate",
+            Self::VisitDuration => "visit_duration",
+            Self::ViewsPerVisit => "views_per_visit",
+        }
+    }
+
+    /// Parses a comma-separated list of metrics, like the `metrics` query parameter.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if any metric is unknown.
+    pub fn parse_list(metrics: &str) -> Result<Vec<Self>, InvalidStatsQuery> {
+        metrics
+            .split(',')
+            .map(|metric| metric.trim().parse())
+            .collect()
+    }
+}
+
+impl FromStr for Metric {
+    type Err = InvalidStatsQuery;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|metric| metric.as_str() == s)
+            .ok_or_else(|| InvalidStatsQuery(format!("unknown metric {s:?}")))
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Property stats can be filtered and broken down by.
+///
+/// `event:` properties are those of each event, `visit:` properties are those of the first event
+/// of its visit.
+///
+/// See: <https://plausible.io/docs/stats-api#properties>
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Property {
+    /// `event:name`
+    EventName,
+
+    /// `event:page`, the path of the page.
+    EventPage,
+
+    /// `visit:entry_page`, the path of the first page of the visit.
+    VisitEntryPage,
+
+    /// `visit:source`, e.g. the referrer's domain or `utm_source`. `Direct / None` if unknown.
+    VisitSource,
+
+    /// `visit:utm_source`
+    VisitUtmSource,
+
+    /// `visit:utm_medium`
+    VisitUtmMedium,
+
+    /// `visit:utm_campaign`
+    VisitUtmCampaign,
+
+    /// `event:props:<key>`, a custom property.
+    EventProp(String),
+}
+
+impl Property {
+    /// Returns the key of this property in breakdown results, e.g. `page` for `event:page`.
+    #[must_use]
+    pub fn key(&self) -> &str {
+        match self {
+            Self::EventName => "name",
+            Self::EventPage => "page",
+            Self::VisitEntryPage => "entry_page",
+            Self::VisitSource => "source",
+            Self::VisitUtmSource => "utm_source",
+            Self::VisitUtmMedium => "utm_medium",
+            Self::VisitUtmCampaign => "utm_campaign",
+            Self::EventProp(key) => key,
+        }
+    }
+}
+
+impl FromStr for Property {
+    type Err = InvalidStatsQuery;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "event:name" => Self::EventName,
+            "event:page" => Self::EventPage,
+            "visit:entry_page" => Self::VisitEntryPage,
+            "visit:source" => Self::VisitSource,
+            "visit:utm_source" => Self::VisitUtmSource,
+            "visit:utm_medium" => Self::VisitUtmMedium,
+            "visit:utm_campaign" => Self::VisitUtmCampaign,
+            _ => match s.strip_prefix("event:props:") {
+                Some(key) if !key.is_empty() => Self::EventProp(key.to_string()),
+                _ => return Err(InvalidStatsQuery(format!("unknown property {s:?}"))),
+            },
+        })
+    }
+}
+
+impl fmt::Display for Property {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EventName | Self::EventPage => write!(f, "event:{}", self.key()),
+            Self::EventProp(key) => write!(f, "event:props:{key}"),
+            _ => write!(f, "visit:{}", self.key()),
+        }
+    }
+}
+
+/// Range of days, in UTC, that stats are computed for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    /// Every event ever received.
+    All,
+
+    /// A single day.
+    Day(NaiveDate),
+
+    /// From the first to the last day, inclusive.
+    Custom { from: NaiveDate, to: NaiveDate },
+}
+
+impl Period {
+    /// Parses the `period` and `date` query parameters of the Stats API.
+    ///
+    /// Relative periods such as `7d` end on `date`, or on `today` if `date` is missing.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the period is unknown or a date is invalid.
+    pub fn parse(
+        period: &str,
+        date: Option<&str>,
+        today: NaiveDate,
+    ) -> Result<Self, InvalidStatsQuery> {
+        if period == "custom" {
+            let (from, to) = date.and_then(|date| date.split_once(',')).ok_or_else(|| {
+                InvalidStatsQuery(String::from(
+                    "the custom period requires `date=YYYY-MM-DD,YYYY-MM-DD`",
+                ))
+            })?;
+            return Ok(Self::Custom {
+                from: parse_date(from)?,
+                to: parse_date(to)?,
+            });
+        }
+
+        let date: NaiveDate = date.map_or(Ok(today), parse_date)?;
+        let first_of_month: NaiveDate = date.with_day(1).unwrap_or(date);
+        let last_days = |days: u64| Self::Custom {
+            from: date - Days::new(days - 1),
+            to: date,
+        };
+        let last_months = |months: u32| Self::Custom {
+            from: first_of_month - Months::new(months - 1),
+            to: first_of_month + Months::new(1) - Days::new(1),
+        };
+        Ok(match period {
+            "day" => Self::Day(date),
+            "7d" => last_days(7),
+            "30d" => last_days(30),
+            "month" => last_months(1),
+            "6mo" => last_months(6),
+            "12mo" => last_months(12),
+            _ => {
+                return Err(InvalidStatsQuery(format!("unknown period {period:?}")));
+            }
+        })
+    }
+
+    /// Returns the first and last day of the period, if it is bounded.
+    #[must_use]
+    pub const fn bounds(&self) -> Option<(NaiveDate, NaiveDate)> {
+        match *self {
+            Self::All => None,
+            Self::Day(date) => Some((date, date)),
+            Self::Custom { from, to } => Some((from, to)),
+        }
+    }
+
+    /// Returns whether `date` is within the period.
+    #[must_use]
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.bounds()
+            .is_none_or(|(from, to)| from <= date && date <= to)
+    }
+}
+
+/// Size of the buckets of a timeseries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Interval {
+    /// One bucket per day.
+    #[default]
+    Date,
+
+    /// One bucket per calendar month, keyed by its first day.
+    Month,
+}
+
+impl FromStr for Interval {
+    type Err = InvalidStatsQuery;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "date" => Ok(Self::Date),
+            "month" => Ok(Self::Month),
+            _ => Err(InvalidStatsQuery(format!("unknown interval {s:?}"))),
+        }
+    }
+}
+
+/// Query for the stats of a site, answered by the `Emulator`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatsQuery {
+    /// Domain of the site, as set in `EventPayload::domain`.
+    pub site_id: String,
+
+    pub period: Period,
+
+    /// Only events whose property equals the value are counted.
+    pub filters: Vec<(Property, String)>,
+}
+
+impl StatsQuery {
+    /// Query every event received for the site `site_id`.
+    #[must_use]
+    pub fn new(site_id: &str) -> Self {
+        Self {
+            site_id: site_id.to_string(),
+            period: Period::All,
+            filters: Vec::new(),
+        }
+    }
+
+    pub const fn period(&mut self, period: Period) -> &mut Self {
+        self.period = period;
+        self
+    }
+
+    /// Only count events whose `property` equals `value`.
+    pub fn filter(&mut self, property: Property, value: &str) -> &mut Self {
+        self.filters.push((property, value.to_string()));
+        self
+    }
+
+    /// Parses the `filters` query parameter, e.g. `event:page==/blog;visit:source==Google`.
+    ///
+    /// Only the `==` operator is supported.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if a filter has another operator or an unknown property.
+    pub fn parse_filters(filters: &str) -> Result<Vec<(Property, String)>, InvalidStatsQuery> {
+        filters
+            .split(';')
+            .filter(|filter| !filter.trim().is_empty())
+            .map(|filter| {
+                let (property, value) = filter
+                    .split_once("==")
+                    .ok_or_else(|| InvalidStatsQuery(format!("unsupported filter {filter:?}")))?;
+                Ok((property.trim().parse()?, value.trim().to_string()))
+            })
+            .collect()
+    }
+}
+
+/// Every metric, computed for a set of events.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Metrics {
+    pub visitors: u64,
+    pub visits: u64,
+    pub pageviews: u64,
+    pub events: u64,
+    pub bounce_rate: u64,
+    pub visit_duration: u64,
+    pub views_per_visit: f64,
+}
+
+impl Metrics {
+    /// Returns the value of `metric`, as it appears in Stats API responses.
+    #[must_use]
+    pub fn value(&self, metric: Metric) -> Value {
+        match metric {
+            Metric::Visitors => Value::from(self.visitors),
+            Metric::Visits => Value::from(self.visits),
+            Metric::Pageviews => Value::from(self.pageviews),
+            Metric::Events => Value::from(self.events),
+            Metric::BounceRate => Value::from(self.bounce_rate),
+            Metric::VisitDuration => Value::from(self.visit_duration),
+            Metric::ViewsPerVisit => Value::from(self.views_per_visit),
+        }
+    }
+}
+
+/// Metrics of one bucket of a timeseries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeseriesPoint {
+    /// First day of the bucket.
+    pub date: NaiveDate,
+    pub metrics: Metrics,
+}
+
+/// Metrics of the events sharing one value of the property broken down by.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BreakdownRow {
+    pub value: String,
+    pub metrics: Metrics,
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, InvalidStatsQuery> {
+    NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|e| InvalidStatsQuery(format!("invalid date {date:?}: {e}")))
+}
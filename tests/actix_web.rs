@@ -0,0 +1,116 @@
+pub mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::http::header::ContentType;
+use actix_web::test::TestRequest;
+use actix_web::{App, HttpResponse, test, web};
+use common::TEST_CLIENT_IP;
+use plausible_rs::integrations::actix_web::{PlausibleMiddleware, PlausibleRequest, SkipTracking};
+use plausible_rs::test_util::PlausibleServer;
+use plausible_rs::{EventRecord, PAGEVIEW_EVENT, TrustedProxies, UrlFilter};
+use std::time::Duration;
+
+fn html(body: &'static str) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .body(body)
+}
+
+fn middleware(server: &PlausibleServer) -> PlausibleMiddleware {
+    let mut url_filter: UrlFilter = UrlFilter::new();
+    url_filter.exclude("/admin/**");
+    let mut middleware: PlausibleMiddleware =
+        PlausibleMiddleware::new(server.client(), String::from("example.com"));
+    middleware
+        .url_filter(url_filter)
+        .trusted_proxies(TrustedProxies::with_cidrs(&["10.0.0.0/8"]).unwrap());
+    middleware
+}
+
+fn request(path: &str) -> TestRequest {
+    TestRequest::get()
+        .uri(path)
+        .insert_header(("host", "example.com"))
+        .insert_header(("x-forwarded-for", format!("1.2.3.4, {TEST_CLIENT_IP}")))
+        .insert_header(("referer", "https://www.google.com/"))
+        .peer_addr("10.0.0.1:51234".parse().unwrap())
+}
+
+#[actix_web::test]
+async fn test_pageviews() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    let app = test::init_service(
+        App::new()
+            .wrap(middleware(&server))
+            .route("/", web::get().to(|| async { html("<h1>Home</h1>") }))
+            .route("/api", web::get().to(|| async { "plain text" }))
+            .route(
+                "/private",
+                web::get().to(|| async {
+                    let mut response: HttpResponse = html("<h1>Private</h1>");
+                    response.extensions_mut().insert(SkipTracking);
+                    response
+                }),
+            )
+            .route(
+                "/admin/users",
+                web::get().to(|| async { html("<h1>Admin</h1>") }),
+            )
+            .route(
+                "/extract",
+                web::get().to(|request: PlausibleRequest| async move {
+                    format!("{} {}", request.headers.x_forwarded_for, request.url)
+                }),
+            ),
+    )
+    .await;
+
+    for path in ["/api", "/private", "/admin/users", "/missing", "/"] {
+        test::call_service(&app, request(path).to_request()).await;
+    }
+    assert_eq!(
+        test::call_and_read_body(&app, request("/extract?page=2").to_request()).await,
+        format!("{TEST_CLIENT_IP} http://example.com/extract?page=2")
+    );
+
+    // pageviews are sent in the background
+    let events: Vec<EventRecord> = server
+        .mock()
+        .wait_for_events(1, Duration::from_secs(5))
+        .await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].payload.name, PAGEVIEW_EVENT);
+    assert_eq!(events[0].payload.url, "http://example.com/");
+    assert_eq!(
+        events[0].payload.referrer.as_deref(),
+        Some("https://www.google.com/")
+    );
+    assert_eq!(events[0].headers.x_forwarded_for, TEST_CLIENT_IP);
+}
+
+#[actix_web::test]
+async fn test_extractor() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    let app = test::init_service(App::new().wrap(middleware(&server)).route(
+        "/extract",
+        web::get().to(|request: PlausibleRequest| async move { request.url }),
+    ))
+    .await;
+
+    // forwarding headers sent by clients themselves are ignored
+    let request = TestRequest::get()
+        .uri("/extract")
+        .insert_header(("host", "example.com"))
+        .insert_header(("x-forwarded-host", "evil.example"))
+        .insert_header(("x-forwarded-proto", "https"))
+        .peer_addr("198.51.100.1:51234".parse().unwrap());
+    assert_eq!(
+        test::call_and_read_body(&app, request.to_request()).await,
+        "http://example.com/extract"
+    );
+
+    // neither a host nor a client IP address, without leaking why
+    let response = test::call_service(&app, TestRequest::get().uri("/extract").to_request()).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert!(test::read_body(response).await.is_empty());
+}
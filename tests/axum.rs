@@ -0,0 +1,156 @@
+pub mod common;
+
+use axum::Router;
+use axum::body::Body;
+use axum::extract::ConnectInfo;
+use axum::http::{Request, StatusCode};
+use axum::response::{Html, Response};
+use axum::routing::get;
+use common::{TEST_CLIENT_IP, TEST_USER_AGENT};
+use plausible_rs::integrations::axum::{PlausibleLayer, PlausibleRequest, SkipTracking};
+use plausible_rs::test_util::PlausibleServer;
+use plausible_rs::{EventRecord, PAGEVIEW_EVENT, PRIVATE_NETWORKS, TrustedProxies, UrlFilter};
+use std::net::SocketAddr;
+use std::time::Duration;
+use tower::ServiceExt;
+
+fn router(server: &PlausibleServer) -> Router {
+    let mut url_filter: UrlFilter = UrlFilter::new();
+    url_filter.exclude("/admin/**");
+    let mut layer: PlausibleLayer =
+        PlausibleLayer::new(server.client(), String::from("example.com"));
+    layer
+        .url_filter(url_filter)
+        .trusted_proxies(TrustedProxies::with_cidrs(PRIVATE_NETWORKS).unwrap());
+
+    Router::new()
+        .route("/", get(|| async { Html("<h1>Home</h1>") }))
+        .route("/api", get(|| async { "plain text" }))
+        .route(
+            "/private",
+            get(|| async { (SkipTracking, Html("<h1>Private</h1>")) }),
+        )
+        .route(
+            "/missing",
+            get(|| async { (StatusCode::NOT_FOUND, Html("<h1>Not Found</h1>")) }),
+        )
+        .route("/admin/users", get(|| async { Html("<h1>Admin</h1>") }))
+        .route(
+            "/extract",
+            get(|request: PlausibleRequest| async move { request.url }),
+        )
+        .layer(layer)
+}
+
+/// Sends a request for `path` through a load balancer at `peer`, as axum's `ConnectInfo`.
+async fn send(router: &Router, path: &str, peer: &str) -> Response {
+    let mut request: Request<Body> = Request::get(path)
+        .header("host", "example.com")
+        .header("x-forwarded-for", TEST_CLIENT_IP)
+        .header("x-forwarded-proto", "https")
+        .header("referer", "https://www.google.com/")
+        .body(Body::empty())
+        .unwrap();
+    request
+        .extensions_mut()
+        .insert(ConnectInfo(peer.parse::<SocketAddr>().unwrap()));
+    router.clone().oneshot(request).await.unwrap()
+}
+
+async fn body(response: Response) -> String {
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+async fn get_page(router: &Router, path: &str) -> String {
+    body(send(router, path, "10.0.0.1:51234").await).await
+}
+
+#[tokio::test]
+async fn test_pageviews() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    let router: Router = router(&server);
+
+    for path in ["/api", "/private", "/missing", "/admin/users", "/"] {
+        get_page(&router, path).await;
+    }
+
+    // pageviews are sent in the background
+    let events: Vec<EventRecord> = server
+        .mock()
+        .wait_for_events(1, Duration::from_secs(5))
+        .await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].payload.name, PAGEVIEW_EVENT);
+    assert_eq!(events[0].payload.url, "https://example.com/");
+    assert_eq!(
+        events[0].payload.referrer.as_deref(),
+        Some("https://www.google.com/")
+    );
+    assert_eq!(events[0].headers.x_forwarded_for, TEST_CLIENT_IP);
+}
+
+#[tokio::test]
+async fn test_extractor() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    assert_eq!(
+        get_page(&router(&server), "/extract?page=2").await,
+        "https://example.com/extract?page=2"
+    );
+}
+
+#[tokio::test]
+async fn test_untrusted_peer() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+
+    // forwarding headers sent by clients themselves are ignored
+    assert_eq!(
+        body(send(&router(&server), "/extract", "198.51.100.1:51234").await).await,
+        "http://example.com/extract"
+    );
+}
+
+#[tokio::test]
+async fn test_rejection() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+
+    // neither a host nor a client IP address, without leaking why
+    let response: Response = router(&server)
+        .oneshot(Request::get("/extract").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(body(response).await, "");
+}
+
+#[tokio::test]
+async fn test_connect_info() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr: SocketAddr = listener.local_addr().unwrap();
+    tokio::spawn(
+        axum::serve(
+            listener,
+            router(&server).into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .into_future(),
+    );
+
+    // a direct request, without forwarding headers
+    reqwest::Client::new()
+        .get(format!("http://{addr}/"))
+        .header("user-agent", TEST_USER_AGENT)
+        .send()
+        .await
+        .unwrap();
+
+    let events: Vec<EventRecord> = server
+        .mock()
+        .wait_for_events(1, Duration::from_secs(5))
+        .await;
+    assert_eq!(events[0].payload.url, format!("http://{addr}/"));
+    assert_eq!(events[0].headers.user_agent, TEST_USER_AGENT);
+    assert_eq!(events[0].headers.x_forwarded_for, "127.0.0.1");
+}
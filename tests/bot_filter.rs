@@ -0,0 +1,75 @@
+use plausible_rs::{
+    BotFilter, EventHeaders, EventOutcome, EventPayload, PAGEVIEW_EVENT, Plausible,
+};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const CHROME: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/105.0.0.0 Safari/537.36";
+const GOOGLEBOT: &str = "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)";
+const HEADLESS_CHROME: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) HeadlessChrome/105.0.0.0 Safari/537.36";
+const SLACK: &str = "Slackbot-LinkExpanding 1.0 (+https://api.slack.com/robots)";
+const WHATSAPP: &str = "WhatsApp/2.23.20.0 A";
+
+/// Browsers whose User-Agent contains words that bots' User-Agents also contain.
+const BROWSERS: &[&str] = &[
+    // a CUBOT phone
+    "Mozilla/5.0 (Linux; Android 10; CUBOT_X30) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/89.0.4389.105 Mobile Safari/537.36",
+    "Mozilla/5.0 (Linux; Android 11; CUBOT KINGKONG 7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/96.0.4664.104 Mobile Safari/537.36",
+    // WhatsApp's in-app browser
+    "Mozilla/5.0 (Linux; Android 13; SM-A536B Build/TP1A.220624.014; wv) AppleWebKit/537.36 (KHTML, like Gecko) Version/4.0 Chrome/116.0.5845.163 Mobile Safari/537.36 WhatsApp/2.23.18.78",
+    // apps embedding a web view, named after what they do
+    "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Mobile/15E148 PreviewApp/3.1",
+    "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36 UptimeStatus/1.4",
+];
+
+#[test]
+fn test_is_bot() {
+    let mut bot_filter: BotFilter = BotFilter::new();
+    assert!(!bot_filter.is_bot(CHROME));
+    assert!(bot_filter.is_bot(GOOGLEBOT));
+    assert!(bot_filter.is_bot(HEADLESS_CHROME));
+    assert!(bot_filter.is_bot(SLACK));
+    assert!(bot_filter.is_bot(WHATSAPP));
+    assert!(bot_filter.is_bot("curl/8.4.0"));
+    for browser in BROWSERS {
+        assert!(!bot_filter.is_bot(browser), "{browser}");
+    }
+
+    // allowed agents are never bots
+    bot_filter.allow("Slackbot");
+    assert!(!bot_filter.is_bot(SLACK));
+
+    // extra patterns can be added
+    bot_filter.deny("Chrome/105");
+    assert!(bot_filter.is_bot(CHROME));
+}
+
+#[tokio::test]
+async fn test_event_bot_filtered() {
+    let filtered: Arc<AtomicUsize> = Arc::new(AtomicUsize::new(0));
+    let handler_filtered = filtered.clone();
+
+    let plausible: Plausible = Plausible::builder()
+        .base_url(String::from("http://127.0.0.1:1"))
+        .bot_filter(BotFilter::new())
+        .on_bot_filtered(move |_, _| {
+            handler_filtered.fetch_add(1, Ordering::Relaxed);
+        })
+        .build();
+
+    // post Event, which is dropped before being sent
+    let outcome: EventOutcome = plausible
+        .event(
+            EventHeaders::new(GOOGLEBOT.to_string(), String::from("66.249.66.1")),
+            EventPayload::builder(
+                String::from("example.com"),
+                PAGEVIEW_EVENT.to_string(),
+                String::from("https://example.com/test"),
+            )
+            .build(),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(outcome, EventOutcome::BotFiltered));
+    assert_eq!(filtered.load(Ordering::Relaxed), 1);
+}
@@ -0,0 +1,65 @@
+pub mod common;
+
+use common::{TEST_USER_AGENT, test_headers, test_pageview};
+use plausible_rs::test_util::{MockResponse, PlausibleServer};
+use plausible_rs::{Cassette, CassetteFile, Error, EventOutcome, Plausible, REDACTED};
+use std::path::PathBuf;
+
+#[tokio::test]
+async fn test_record_and_replay() {
+    let path: PathBuf =
+        std::env::temp_dir().join(format!("plausible-rs-cassette-{}.json", std::process::id()));
+
+    // record against a local server
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    server.mock().respond_to_next_event(MockResponse::Dropped);
+    let recording: Plausible = Plausible::builder()
+        .base_url(server.base_url())
+        .cassette(Cassette::record(&path))
+        .build();
+    recording
+        .event(test_headers(), test_pageview("/a"))
+        .await
+        .unwrap();
+    recording.health().await.unwrap();
+    let base_url: String = server.base_url();
+    drop(server);
+
+    // IP addresses and auth headers are redacted
+    let contents: String = std::fs::read_to_string(&path).unwrap();
+    assert!(!contents.contains("203.0.113.7"));
+    assert!(!contents.contains("127.0.0.1"));
+    let cassette: CassetteFile = serde_json::from_str(&contents).unwrap();
+    assert_eq!(cassette.interactions.len(), 2);
+    assert_eq!(
+        cassette.interactions[0].request.headers["x-forwarded-for"],
+        REDACTED
+    );
+
+    // versions in the User-Agent aren't mistaken for IP addresses
+    assert_eq!(
+        cassette.interactions[0].request.headers["user-agent"],
+        TEST_USER_AGENT
+    );
+
+    // replay without a server
+    let replaying: Plausible = Plausible::builder()
+        .base_url(base_url)
+        .cassette(Cassette::replay(&path))
+        .build();
+    let outcome: EventOutcome = replaying
+        .event(test_headers(), test_pageview("/a"))
+        .await
+        .unwrap();
+    assert!(matches!(outcome, EventOutcome::Dropped(_)));
+    assert_eq!(replaying.health().await.unwrap().clickhouse, "ok");
+
+    // requests that weren't recorded fail
+    let error: Error = replaying
+        .event(test_headers(), test_pageview("/b"))
+        .await
+        .unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(error, Error::UnmatchedRequest(_)));
+    assert!(error.to_string().contains("https://example.com/b"));
+}
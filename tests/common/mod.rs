@@ -0,0 +1,31 @@
+//! Fixtures shared by the integration tests.
+//!
+//! Tests declare this module `pub`, so that fixtures a test doesn't use aren't reported as dead code.
+
+use plausible_rs::{EventHeaders, EventPayload, PAGEVIEW_EVENT};
+
+/// User-Agent of a desktop Chrome browser, sent by `test_headers`.
+pub const TEST_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/105.0.0.0 Safari/537.36";
+
+/// Client IP address sent by `test_headers`, from a range reserved for documentation.
+pub const TEST_CLIENT_IP: &str = "203.0.113.7";
+
+/// Domain of the site `test_pageview` records events for.
+pub const TEST_DOMAIN: &str = "example.com";
+
+/// Returns the headers of a visitor browsing with `TEST_USER_AGENT` from `TEST_CLIENT_IP`.
+#[must_use]
+pub fn test_headers() -> EventHeaders {
+    EventHeaders::new(TEST_USER_AGENT.to_string(), TEST_CLIENT_IP.to_string())
+}
+
+/// Returns a pageview of `path`, e.g. `/blog`, on the site `TEST_DOMAIN`.
+#[must_use]
+pub fn test_pageview(path: &str) -> EventPayload {
+    EventPayload::builder(
+        TEST_DOMAIN.to_string(),
+        PAGEVIEW_EVENT.to_string(),
+        format!("https://{TEST_DOMAIN}{path}"),
+    )
+    .build()
+}
@@ -0,0 +1,104 @@
+pub mod common;
+
+use common::{test_headers, test_pageview};
+use plausible_rs::test_util::PlausibleServer;
+use plausible_rs::{ConsentPolicy, EventHeaders, EventOutcome, Plausible, PrivacySignals};
+
+fn headers(privacy_signals: PrivacySignals) -> EventHeaders {
+    test_headers().with_privacy_signals(privacy_signals)
+}
+
+#[test]
+fn test_privacy_signals() {
+    assert!(!PrivacySignals::from_header_values(None, None).opted_out());
+    assert!(!PrivacySignals::from_header_values(Some("0"), None).opted_out());
+    assert!(PrivacySignals::from_header_values(Some("1"), None).opted_out());
+    assert!(PrivacySignals::from_header_values(None, Some(" 1")).opted_out());
+}
+
+#[tokio::test]
+async fn test_event_consent_withheld() {
+    let plausible: Plausible = Plausible::builder()
+        .base_url(String::from("http://127.0.0.1:1"))
+        .consent_policy(ConsentPolicy::Drop)
+        .build();
+
+    // post Event, which is dropped before being sent
+    let outcome: EventOutcome = plausible
+        .event(
+            headers(PrivacySignals::new(true, false)),
+            test_pageview("/test"),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(outcome, EventOutcome::ConsentWithheld));
+
+    // events without privacy signals are sent, and fail to reach the server
+    assert!(
+        plausible
+            .event(headers(PrivacySignals::default()), test_pageview("/test"))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_event_anonymized_dry_run() {
+    let plausible: Plausible = Plausible::builder()
+        .consent_policy(ConsentPolicy::Anonymize)
+        .dry_run(true)
+        .build();
+
+    let outcome: EventOutcome = plausible
+        .event(
+            headers(PrivacySignals::new(false, true)),
+            test_pageview("/test"),
+        )
+        .await
+        .unwrap();
+    assert!(outcome.is_anonymized());
+    assert!(!outcome.is_consent_ignored());
+    let EventOutcome::DryRun(request) = outcome else {
+        panic!("expected a dry run, got {outcome:?}");
+    };
+    assert!(!request.headers.contains_key("x-forwarded-for"));
+
+    // events without privacy signals are sent as is
+    let outcome: EventOutcome = plausible
+        .event(headers(PrivacySignals::default()), test_pageview("/test"))
+        .await
+        .unwrap();
+    assert!(!outcome.is_anonymized());
+}
+
+#[tokio::test]
+async fn test_event_consent_ignored() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    let plausible: Plausible = Plausible::builder()
+        .base_url(server.base_url())
+        .consent_policy(ConsentPolicy::Ignore)
+        .build();
+
+    // the event is sent as is, and the caller is told which signals were ignored
+    let outcome: EventOutcome = plausible
+        .event(
+            headers(PrivacySignals::new(true, false)),
+            test_pageview("/test"),
+        )
+        .await
+        .unwrap();
+    assert!(outcome.is_accepted());
+    assert!(outcome.is_consent_ignored());
+    assert_eq!(
+        outcome.privacy_signals(),
+        Some(PrivacySignals::new(true, false))
+    );
+
+    // events without privacy signals ignore nothing
+    let outcome: EventOutcome = plausible
+        .event(headers(PrivacySignals::default()), test_pageview("/test"))
+        .await
+        .unwrap();
+    assert!(!outcome.is_consent_ignored());
+    assert_eq!(outcome.privacy_signals(), Some(PrivacySignals::default()));
+}
@@ -0,0 +1,142 @@
+pub mod common;
+
+use common::test_headers;
+use plausible_rs::test_util::{MockResponse, PlausibleServer};
+use plausible_rs::{
+    DedupStore, Deduplication, EventOutcome, EventPayload, InMemoryDedupStore, PAGEVIEW_EVENT,
+    Plausible, PropValue,
+};
+use reqwest::StatusCode;
+use std::collections::HashMap;
+use std::time::Duration;
+
+fn payload(author: &str) -> EventPayload {
+    EventPayload::builder(
+        String::from("example.com"),
+        PAGEVIEW_EVENT.to_string(),
+        String::from("https://example.com/test"),
+    )
+    .props(HashMap::from([
+        (String::from("author"), PropValue::from(author.to_string())),
+        (String::from("year"), PropValue::from(2025)),
+    ]))
+    .build()
+}
+
+#[test]
+fn test_key() {
+    // derived keys are stable for equal events, even though props are a `HashMap`
+    assert_eq!(
+        Deduplication::key(&test_headers(), &payload("Todd")),
+        Deduplication::key(&test_headers(), &payload("Todd"))
+    );
+    assert_ne!(
+        Deduplication::key(&test_headers(), &payload("Todd")),
+        Deduplication::key(&test_headers(), &payload("Everett"))
+    );
+
+    // idempotency keys take precedence
+    let mut payload: EventPayload = payload("Todd");
+    payload.idempotency_key = Some(String::from("purchase-42"));
+    assert_eq!(Deduplication::key(&test_headers(), &payload), "purchase-42");
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_in_memory_store() {
+    let store: InMemoryDedupStore = InMemoryDedupStore::new(2);
+    let window: Duration = Duration::from_secs(60);
+
+    // repeats within the window are rejected
+    assert!(store.insert("a", window));
+    assert!(!store.insert("a", window));
+
+    // removed keys may be inserted again
+    store.remove("a");
+    assert!(store.insert("a", window));
+
+    // removed keys don't count towards the capacity
+    assert!(store.insert("b", window));
+    store.remove("b");
+    assert!(store.insert("c", window));
+    assert!(!store.insert("a", window));
+
+    // the oldest key is forgotten once full
+    assert!(store.insert("b", window));
+    assert!(store.insert("a", window));
+
+    // keys expire after the window
+    tokio::time::advance(window).await;
+    assert!(store.insert("c", window));
+}
+
+#[tokio::test]
+async fn test_event_deduplicated() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    let plausible: Plausible = Plausible::builder()
+        .base_url(server.base_url())
+        .deduplication(Deduplication::new(Duration::from_secs(60)))
+        .build();
+
+    // the first event is sent
+    let outcome: EventOutcome = plausible
+        .event(test_headers(), payload("Todd"))
+        .await
+        .unwrap();
+    assert!(matches!(outcome, EventOutcome::Accepted(_)), "{outcome:?}");
+
+    // its repeat within the window isn't, while other events still are
+    let outcome: EventOutcome = plausible
+        .event(test_headers(), payload("Todd"))
+        .await
+        .unwrap();
+    assert!(matches!(outcome, EventOutcome::Deduplicated), "{outcome:?}");
+    let outcome: EventOutcome = plausible
+        .event(test_headers(), payload("Everett"))
+        .await
+        .unwrap();
+    assert!(matches!(outcome, EventOutcome::Accepted(_)), "{outcome:?}");
+
+    server.mock().assert_event_count(2);
+}
+
+#[tokio::test]
+async fn test_failed_not_remembered() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    server
+        .mock()
+        .respond_to_next_event(MockResponse::failed(StatusCode::SERVICE_UNAVAILABLE));
+    let plausible: Plausible = Plausible::builder()
+        .base_url(server.base_url())
+        .deduplication(Deduplication::new(Duration::from_secs(60)))
+        .build();
+
+    // the failed event wasn't recorded, so its retry is sent
+    plausible
+        .event(test_headers(), payload("Todd"))
+        .await
+        .unwrap_err();
+    let outcome: EventOutcome = plausible
+        .event(test_headers(), payload("Todd"))
+        .await
+        .unwrap();
+    assert!(matches!(outcome, EventOutcome::Accepted(_)), "{outcome:?}");
+
+    server.mock().assert_event_count(2);
+}
+
+#[tokio::test]
+async fn test_dry_run_not_remembered() {
+    let plausible: Plausible = Plausible::builder()
+        .dry_run(true)
+        .deduplication(Deduplication::new(Duration::from_secs(60)))
+        .build();
+
+    // events logged in dry-run mode weren't sent, so their repeats aren't suppressed
+    for _ in 0..2 {
+        let outcome: EventOutcome = plausible
+            .event(test_headers(), payload("Todd"))
+            .await
+            .unwrap();
+        assert!(matches!(outcome, EventOutcome::DryRun(_)), "{outcome:?}");
+    }
+}
@@ -0,0 +1,49 @@
+use plausible_rs::{
+    EventHeaders, EventOutcome, EventPayload, PAGEVIEW_EVENT, Plausible, PropValue,
+};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+#[tokio::test]
+async fn test_dry_run() {
+    // nothing listens on port 1, so sending would fail
+    let plausible: Plausible = Plausible::builder()
+        .base_url(String::from("http://127.0.0.1:1"))
+        .dry_run(true)
+        .build();
+
+    let outcome: EventOutcome = plausible
+        .event(
+            EventHeaders::new(String::from("Mozilla/5.0"), String::from("127.0.0.1")),
+            EventPayload::builder(
+                String::from("example.com"),
+                PAGEVIEW_EVENT.to_string(),
+                String::from("https://example.com/test"),
+            )
+            .props(HashMap::from([(
+                String::from("author"),
+                PropValue::from(String::from("Todd Everett Griffin")),
+            )]))
+            .build(),
+        )
+        .await
+        .unwrap();
+
+    let EventOutcome::DryRun(request) = outcome else {
+        panic!("expected a dry run, got {outcome:?}");
+    };
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.url.as_str(), "http://127.0.0.1:1/api/event");
+    assert_eq!(request.headers["user-agent"], "Mozilla/5.0");
+    assert_eq!(request.headers["x-forwarded-for"], "127.0.0.1");
+    assert_eq!(request.headers["content-type"], "application/json");
+    assert_eq!(
+        serde_json::from_str::<Value>(&request.body).unwrap(),
+        json!({
+            "domain": "example.com",
+            "name": "pageview",
+            "url": "https://example.com/test",
+            "props": { "author": "Todd Everett Griffin" },
+        })
+    );
+}
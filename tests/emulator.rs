@@ -0,0 +1,225 @@
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use plausible_rs::test_util::{
+    BreakdownRow, DIRECT_SOURCE, Emulator, Interval, Metrics, Period, PlausibleServer, Property,
+    StatsQuery, TimeseriesPoint,
+};
+use plausible_rs::{EventHeaders, EventPayload, PAGEVIEW_EVENT, PropValue};
+use std::collections::HashMap;
+
+fn headers(ip: &str) -> EventHeaders {
+    EventHeaders::new(
+        String::from(
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/105.0.0.0 Safari/537.36",
+        ),
+        ip.to_string(),
+    )
+}
+
+fn pageview(url: &str, referrer: Option<&str>) -> EventPayload {
+    let mut payload: EventPayload = EventPayload::builder(
+        String::from("example.com"),
+        PAGEVIEW_EVENT.to_string(),
+        format!("https://example.com{url}"),
+    )
+    .build();
+    payload.referrer = referrer.map(ToString::to_string);
+    payload
+}
+
+fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, day, hour, minute, 0).unwrap()
+}
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+}
+
+/// Two visitors on March 1st, one of them visiting twice, and one visitor on March 3rd.
+fn emulator() -> Emulator {
+    let emulator: Emulator = Emulator::new();
+
+    // first visit: 2 pageviews and a signup over 10 minutes, from a newsletter
+    emulator.ingest_at(
+        &headers("10.0.0.1"),
+        &pageview("/?utm_source=newsletter", None),
+        at(1, 9, 0),
+    );
+    emulator.ingest_at(
+        &headers("10.0.0.1"),
+        &pageview("/pricing", None),
+        at(1, 9, 5),
+    );
+    let mut signup: EventPayload = pageview("/pricing", None);
+    signup.name = String::from("Signup");
+    signup.props = Some(HashMap::from([(
+        String::from("plan"),
+        PropValue::from(String::from("pro")),
+    )]));
+    emulator.ingest_at(&headers("10.0.0.1"), &signup, at(1, 9, 10));
+
+    // second visit of the same visitor, more than 30 minutes later: a bounce
+    emulator.ingest_at(&headers("10.0.0.1"), &pageview("/blog", None), at(1, 12, 0));
+
+    // another visitor, from a search engine
+    emulator.ingest_at(
+        &headers("10.0.0.2"),
+        &pageview("/blog", Some("https://www.google.com/")),
+        at(1, 13, 0),
+    );
+
+    // the first visitor again, counted as a new visitor on another day
+    emulator.ingest_at(&headers("10.0.0.1"), &pageview("/", None), at(3, 8, 0));
+    emulator
+}
+
+#[test]
+fn test_aggregate() {
+    let emulator: Emulator = emulator();
+
+    let all: Metrics = emulator.aggregate(&StatsQuery::new("example.com"));
+    assert_eq!(all.visitors, 3);
+    assert_eq!(all.visits, 4);
+    assert_eq!(all.pageviews, 5);
+    assert_eq!(all.events, 6);
+    assert_eq!(all.bounce_rate, 75);
+    assert_eq!(all.visit_duration, 150);
+    assert!((all.views_per_visit - 1.25).abs() < f64::EPSILON);
+
+    let day: Metrics =
+        emulator.aggregate(StatsQuery::new("example.com").period(Period::Day(date(1))));
+    assert_eq!((day.visitors, day.visits), (2, 3));
+
+    // other sites are not counted
+    assert_eq!(
+        emulator.aggregate(&StatsQuery::new("other.com")),
+        Metrics::default()
+    );
+}
+
+#[test]
+fn test_filters() {
+    let emulator: Emulator = emulator();
+
+    let signups: Metrics = emulator.aggregate(
+        StatsQuery::new("example.com")
+            .filter(Property::EventName, "Signup")
+            .filter(Property::EventProp(String::from("plan")), "pro"),
+    );
+    assert_eq!(
+        (signups.visitors, signups.events, signups.pageviews),
+        (1, 1, 0)
+    );
+
+    // visit properties are those of the first event of the visit
+    let newsletter: Metrics = emulator
+        .aggregate(StatsQuery::new("example.com").filter(Property::VisitSource, "newsletter"));
+    assert_eq!((newsletter.visits, newsletter.events), (1, 3));
+}
+
+#[test]
+fn test_timeseries() {
+    let emulator: Emulator = emulator();
+
+    let points: Vec<TimeseriesPoint> = emulator.timeseries(
+        StatsQuery::new("example.com").period(Period::Custom {
+            from: date(1),
+            to: date(4),
+        }),
+        Interval::Date,
+    );
+    let visitors: Vec<(NaiveDate, u64)> = points
+        .iter()
+        .map(|point| (point.date, point.metrics.visitors))
+        .collect();
+    assert_eq!(
+        visitors,
+        vec![(date(1), 2), (date(2), 0), (date(3), 1), (date(4), 0)]
+    );
+
+    let months: Vec<TimeseriesPoint> =
+        emulator.timeseries(&StatsQuery::new("example.com"), Interval::Month);
+    assert_eq!(months.len(), 1);
+    assert_eq!(months[0].date, date(1));
+    assert_eq!(months[0].metrics.pageviews, 5);
+}
+
+#[test]
+fn test_breakdown() {
+    let emulator: Emulator = emulator();
+
+    let sources: Vec<(String, u64)> = emulator
+        .breakdown(&StatsQuery::new("example.com"), &Property::VisitSource)
+        .into_iter()
+        .map(|row| (row.value, row.metrics.visits))
+        .collect();
+    assert_eq!(
+        sources,
+        vec![
+            (DIRECT_SOURCE.to_string(), 2),
+            (String::from("google.com"), 1),
+            (String::from("newsletter"), 1),
+        ]
+    );
+
+    let pages: Vec<BreakdownRow> = emulator.breakdown(
+        StatsQuery::new("example.com").period(Period::Day(date(1))),
+        &Property::EventPage,
+    );
+    assert_eq!(pages[0].value, "/blog");
+    assert_eq!(pages[0].metrics.visitors, 2);
+}
+
+#[tokio::test]
+async fn test_stats_api() {
+    let server: PlausibleServer = PlausibleServer::builder()
+        .emulator(emulator())
+        .start()
+        .await
+        .unwrap();
+    let client: reqwest::Client = reqwest::Client::new();
+    let get = |query: &'static str| {
+        let request = client.get(format!("{}/api/v1/stats/{query}", server.base_url()));
+        async move { request.send().await.unwrap() }
+    };
+
+    let aggregate: serde_json::Value =
+        get("aggregate?site_id=example.com&period=7d&date=2024-03-03&metrics=visitors,bounce_rate")
+            .await
+            .json()
+            .await
+            .unwrap();
+    assert_eq!(
+        aggregate,
+        serde_json::json!({
+            "results": { "visitors": { "value": 3 }, "bounce_rate": { "value": 75 } }
+        })
+    );
+
+    let breakdown: serde_json::Value = get(
+        "breakdown?site_id=example.com&period=custom&date=2024-03-01,2024-03-01&property=event:page&limit=1&filters=visit:source%3D%3DDirect%20%2F%20None",
+    )
+    .await
+    .json()
+    .await
+    .unwrap();
+    assert_eq!(
+        breakdown,
+        serde_json::json!({ "results": [{ "page": "/blog", "visitors": 1 }] })
+    );
+
+    // events sent to the server are ingested too
+    server
+        .client()
+        .event(headers("10.0.0.9"), pageview("/", None))
+        .await
+        .unwrap();
+    let realtime: serde_json::Value = get("realtime/visitors?site_id=example.com")
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(realtime, 1);
+
+    let invalid = get("aggregate?site_id=example.com&metrics=clicks").await;
+    assert_eq!(invalid.status(), reqwest::StatusCode::BAD_REQUEST);
+}
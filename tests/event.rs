@@ -1,10 +1,24 @@
-use bytes::Bytes;
-use plausible_rs::{Error, EventHeaders, EventPayload, PAGEVIEW_EVENT, Plausible, PropValue};
+use plausible_rs::test_util::{MockResponse, PlausibleServer};
+use plausible_rs::{
+    Error, EventHeaders, EventOutcome, EventPayload, EventRecord, PAGEVIEW_EVENT, Plausible,
+    PropValue,
+};
+use reqwest::StatusCode;
 use std::collections::HashMap;
 
+fn headers() -> EventHeaders {
+    EventHeaders::new(
+        String::from(
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/105.0.0.0 Safari/537.36",
+        ),
+        String::from("127.0.0.1"),
+    )
+}
+
 #[tokio::test]
 async fn test() {
-    let plausible: Plausible = Plausible::new();
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    let plausible: Plausible = server.client();
 
     // collect payload
     let domain: String = String::from("example.com");
@@ -21,15 +35,97 @@ async fn test() {
     )]))
     .build();
 
-    // collect headers
-    let headers: EventHeaders = EventHeaders::new(
-        String::from(
-            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/105.0.0.0 Safari/537.36",
-        ),
-        String::from("127.0.0.1"),
+    // post Event
+    let event_result: Result<EventOutcome, Error> = plausible.event(headers(), payload).await;
+    assert!(event_result.unwrap().is_accepted());
+
+    // the server received the event as sent over the wire
+    let record: EventRecord = server.mock().assert_event_with_prop(
+        PAGEVIEW_EVENT,
+        "author",
+        String::from("Todd Everett Griffin"),
+    );
+    assert_eq!(record.headers.user_agent, headers().user_agent);
+    assert_eq!(record.headers.x_forwarded_for, "127.0.0.1");
+    assert_eq!(record.payload.url, "https://example.com/test");
+    assert_eq!(
+        record.payload.referrer.as_deref(),
+        Some("https://www.toddgriffin.me/")
     );
+    assert_eq!(record.payload.screen_width, Some(2560));
+}
 
-    // post Event
-    let event_result: Result<Bytes, Error> = plausible.event(headers, payload).await;
-    assert!(event_result.is_ok());
+#[tokio::test]
+async fn test_failed() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    server.mock().respond_to_next_event(MockResponse::Failed {
+        status_code: StatusCode::TOO_MANY_REQUESTS,
+        bytes: bytes::Bytes::from_static(b"slow down"),
+    });
+
+    let error: Error = server
+        .client()
+        .event(
+            headers(),
+            EventPayload::builder(
+                String::from("example.com"),
+                PAGEVIEW_EVENT.to_string(),
+                String::from("https://example.com/test"),
+            )
+            .build(),
+        )
+        .await
+        .unwrap_err();
+    let Error::RequestFailed { bytes, status_code } = error else {
+        panic!("expected the request to fail, got {error:?}");
+    };
+    assert_eq!(status_code, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(bytes, "slow down");
+}
+
+#[tokio::test]
+async fn test_invalid_payload() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+
+    let error: Error = server
+        .client()
+        .event(
+            headers(),
+            EventPayload::builder(String::new(), PAGEVIEW_EVENT.to_string(), String::new()).build(),
+        )
+        .await
+        .unwrap_err();
+    let Error::RequestFailed { bytes, status_code } = error else {
+        panic!("expected the request to fail, got {error:?}");
+    };
+    assert_eq!(status_code, StatusCode::BAD_REQUEST);
+    let errors: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert!(errors["errors"]["domain"].is_array());
+    assert!(errors["errors"]["url"].is_array());
+
+    // rejected requests never reach the mock
+    server.mock().assert_no_events();
+}
+
+#[tokio::test]
+async fn test_connection_address() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+
+    // without X-Forwarded-For, the IP address of the connection is used
+    server
+        .client()
+        .event(
+            EventHeaders::new(String::from("Mozilla/5.0"), String::new()),
+            EventPayload::builder(
+                String::from("example.com"),
+                PAGEVIEW_EVENT.to_string(),
+                String::from("https://example.com/test"),
+            )
+            .build(),
+        )
+        .await
+        .unwrap();
+
+    let record: EventRecord = server.mock().assert_event_received(PAGEVIEW_EVENT);
+    assert_eq!(record.headers.x_forwarded_for, "127.0.0.1");
 }
@@ -0,0 +1,53 @@
+pub mod common;
+
+use common::{test_headers, test_pageview};
+use plausible_rs::{Error, Plausible};
+use std::sync::{Arc, Mutex};
+
+#[tokio::test]
+async fn test_error_handler() {
+    let errors: Arc<Mutex<Vec<Error>>> = Arc::new(Mutex::new(Vec::new()));
+    let handler_errors = errors.clone();
+
+    // nothing listens on port 1, so sending must fail
+    let plausible: Plausible = Plausible::builder()
+        .base_url(String::from("http://127.0.0.1:1"))
+        .on_error(move |e| handler_errors.lock().unwrap().push(e))
+        .build();
+
+    // post Event, wait for the detached task to finish
+    let handle = plausible
+        .event_detached(test_headers(), test_pageview("/test"))
+        .unwrap();
+    handle.await.unwrap();
+
+    let errors = errors.lock().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], Error::ReqwestError(_)));
+}
+
+#[tokio::test]
+async fn test_in_flight_limit() {
+    let errors: Arc<Mutex<Vec<Error>>> = Arc::new(Mutex::new(Vec::new()));
+    let handler_errors = errors.clone();
+
+    let plausible: Plausible = Plausible::builder()
+        .base_url(String::from("http://127.0.0.1:1"))
+        .max_in_flight(0)
+        .on_error(move |e| handler_errors.lock().unwrap().push(e))
+        .build();
+
+    // no permits available, so the event is dropped immediately
+    assert!(
+        plausible
+            .event_detached(test_headers(), test_pageview("/test"))
+            .is_none()
+    );
+
+    let errors = errors.lock().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        errors[0],
+        Error::InFlightLimitReached { limit: 0 }
+    ));
+}
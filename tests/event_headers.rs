@@ -0,0 +1,85 @@
+use http::{HeaderMap, HeaderValue, Request};
+use plausible_rs::{Error, EventHeaders, parse_forwarded, parse_x_forwarded_for};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+fn header_map(headers: &[(&'static str, &'static str)]) -> HeaderMap {
+    let mut map: HeaderMap = HeaderMap::new();
+    for (name, value) in headers {
+        map.append(*name, HeaderValue::from_static(value));
+    }
+    map
+}
+
+#[test]
+fn test_x_forwarded_for() {
+    let headers: EventHeaders = EventHeaders::from_header_map(
+        &header_map(&[
+            ("user-agent", "Mozilla/5.0"),
+            ("x-forwarded-for", "unknown, 203.0.113.7:51234"),
+            ("x-forwarded-for", "10.0.0.1"),
+            ("dnt", "1"),
+        ]),
+        None,
+    )
+    .unwrap();
+    assert_eq!(headers.user_agent, "Mozilla/5.0");
+    assert_eq!(headers.x_forwarded_for, "203.0.113.7");
+    assert!(headers.privacy_signals.do_not_track);
+
+    assert_eq!(
+        parse_x_forwarded_for("2001:db8::1, [2001:db8::2]:443, 198.51.100.1"),
+        vec![
+            "2001:db8::1".parse::<IpAddr>().unwrap(),
+            "2001:db8::2".parse().unwrap(),
+            "198.51.100.1".parse().unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn test_forwarded() {
+    assert_eq!(
+        parse_forwarded(
+            r#"for=_hidden, For="[2001:db8:cafe::17]:4711";proto=https, for=192.0.2.60;by=203.0.113.43"#
+        ),
+        vec![
+            "2001:db8:cafe::17".parse::<IpAddr>().unwrap(),
+            "192.0.2.60".parse().unwrap(),
+        ]
+    );
+
+    // Forwarded is preferred over X-Forwarded-For
+    let headers: EventHeaders = EventHeaders::from_header_map(
+        &header_map(&[
+            ("forwarded", "for=192.0.2.60;proto=http"),
+            ("x-forwarded-for", "203.0.113.7"),
+        ]),
+        None,
+    )
+    .unwrap();
+    assert_eq!(headers.x_forwarded_for, "192.0.2.60");
+    assert_eq!(headers.user_agent, "");
+}
+
+#[test]
+fn test_peer_fallback() {
+    let peer: IpAddr = IpAddr::V4(Ipv4Addr::new(198, 51, 100, 9));
+    let headers: EventHeaders =
+        EventHeaders::from_header_map(&header_map(&[("user-agent", "curl/8.0")]), Some(peer))
+            .unwrap();
+    assert_eq!(headers.x_forwarded_for, "198.51.100.9");
+
+    // from the request's extensions
+    let mut request: Request<()> = Request::new(());
+    request
+        .extensions_mut()
+        .insert(SocketAddr::new(peer, 51234));
+    let (parts, ()) = request.into_parts();
+    let headers: EventHeaders = EventHeaders::from_request_parts(&parts, None).unwrap();
+    assert_eq!(headers.x_forwarded_for, "198.51.100.9");
+
+    assert!(matches!(
+        EventHeaders::from_header_map(&HeaderMap::new(), None),
+        Err(Error::MissingClientIp)
+    ));
+}
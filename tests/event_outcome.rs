@@ -0,0 +1,42 @@
+use plausible_rs::test_util::{MockResponse, PlausibleServer};
+use plausible_rs::{EventHeaders, EventOutcome, EventPayload, PAGEVIEW_EVENT, Plausible};
+
+async fn event(plausible: &Plausible) -> EventOutcome {
+    plausible
+        .event(
+            EventHeaders::new(
+                String::from(
+                    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/105.0.0.0 Safari/537.36",
+                ),
+                String::from("127.0.0.1"),
+            ),
+            EventPayload::builder(
+                String::from("example.com"),
+                PAGEVIEW_EVENT.to_string(),
+                String::from("https://example.com/test"),
+            )
+            .build(),
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_accepted() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+
+    let outcome: EventOutcome = event(&server.client()).await;
+    assert!(outcome.is_accepted());
+    assert_eq!(outcome.response().unwrap().status_code, 202);
+    assert_eq!(outcome.response().unwrap().bytes, "ok");
+}
+
+#[tokio::test]
+async fn test_dropped() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    server.mock().respond_to_next_event(MockResponse::Dropped);
+
+    let outcome: EventOutcome = event(&server.client()).await;
+    assert!(matches!(outcome, EventOutcome::Dropped(_)));
+    assert!(outcome.response().unwrap().is_dropped());
+}
@@ -0,0 +1,69 @@
+pub mod common;
+
+use common::{TEST_CLIENT_IP, test_headers, test_pageview};
+use plausible_rs::{
+    DynEventSink, EventRecord, EventSink, FanOutSink, FileSink, MemorySink, Plausible,
+};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Records an event through a type-erased sink, like application code would.
+async fn record(sink: &DynEventSink, path: &str) {
+    sink.send(test_headers(), test_pageview(path))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_fan_out() {
+    let memory: MemorySink = MemorySink::new();
+    let path: PathBuf =
+        std::env::temp_dir().join(format!("plausible-rs-{}.jsonl", std::process::id()));
+    let file: FileSink = FileSink::open(&path).await.unwrap();
+
+    // Plausible itself is a sink too
+    let plausible: Plausible = Plausible::builder().dry_run(true).build();
+
+    let sink: DynEventSink = Arc::new(FanOutSink::new(vec![
+        Arc::new(memory.clone()),
+        Arc::new(file),
+        plausible.into_event_sink(),
+    ]));
+    record(&sink, "/a").await;
+    record(&sink, "/b").await;
+
+    // every sink received both events
+    let urls: Vec<String> = memory.events().into_iter().map(|e| e.payload.url).collect();
+    assert_eq!(urls, vec!["https://example.com/a", "https://example.com/b"]);
+
+    let lines: Vec<EventRecord> = std::fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1].payload.url, "https://example.com/b");
+    assert_eq!(lines[1].headers.x_forwarded_for, TEST_CLIENT_IP);
+}
+
+#[tokio::test]
+async fn test_fan_out_error() {
+    let memory: MemorySink = MemorySink::new();
+
+    // nothing listens on port 1, so sending to Plausible fails
+    let plausible: Plausible = Plausible::builder()
+        .base_url(String::from("http://127.0.0.1:1"))
+        .build();
+
+    let sink: FanOutSink =
+        FanOutSink::new(vec![plausible.into_event_sink(), Arc::new(memory.clone())]);
+    assert!(
+        sink.send(test_headers(), test_pageview("/a"))
+            .await
+            .is_err()
+    );
+
+    // the other sinks still received the event
+    assert_eq!(memory.events().len(), 1);
+}
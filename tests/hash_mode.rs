@@ -0,0 +1,41 @@
+use plausible_rs::{EventPayload, PAGEVIEW_EVENT, UrlNormalizer};
+use serde_json::{Value, json};
+
+fn payload(hash_mode: bool) -> EventPayload {
+    EventPayload::builder(
+        String::from("example.com"),
+        PAGEVIEW_EVENT.to_string(),
+        String::from("https://example.com/#/users/8123?tab=billing&token=s3cr3t"),
+    )
+    .hash_mode(hash_mode)
+    .build()
+}
+
+#[test]
+fn test_serialize() {
+    // the flag is sent the way the script sends it
+    let value: Value = serde_json::to_value(payload(true)).unwrap();
+    assert_eq!(value["h"], json!(1));
+    let payload: EventPayload = serde_json::from_value(value).unwrap();
+    assert!(payload.hash_mode);
+
+    // and omitted when unset
+    let value: Value = serde_json::to_value(self::payload(false)).unwrap();
+    assert!(value.get("h").is_none());
+}
+
+#[test]
+fn test_normalize_payload() {
+    let mut url_normalizer: UrlNormalizer = UrlNormalizer::new();
+    url_normalizer.route("/users/:id").allow_query_param("tab");
+
+    // the fragment is a route in hash mode
+    let mut payload: EventPayload = payload(true);
+    url_normalizer.normalize_payload(&mut payload);
+    assert_eq!(payload.url, "https://example.com/#/users/:id?tab=billing");
+
+    // and ignored otherwise
+    let mut payload: EventPayload = self::payload(false);
+    url_normalizer.normalize_payload(&mut payload);
+    assert_eq!(payload.url, "https://example.com/");
+}
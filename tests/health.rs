@@ -1,10 +1,25 @@
+use plausible_rs::test_util::{MockResponse, PlausibleServer};
 use plausible_rs::{Error, HealthResponse, Plausible};
+use reqwest::StatusCode;
 
 #[tokio::test]
 async fn test() {
-    let plausible: Plausible = Plausible::new();
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    let plausible: Plausible = server.client();
 
     // get API health
     let health_result: Result<HealthResponse, Error> = plausible.health().await;
-    assert!(health_result.is_ok());
+    assert_eq!(health_result.unwrap().postgres, "ok");
+
+    // scripted failure
+    server
+        .mock()
+        .respond_to_next_health(MockResponse::failed(StatusCode::SERVICE_UNAVAILABLE));
+    assert!(matches!(
+        plausible.health().await,
+        Err(Error::RequestFailed {
+            status_code: StatusCode::SERVICE_UNAVAILABLE,
+            ..
+        })
+    ));
 }
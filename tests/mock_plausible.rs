@@ -0,0 +1,143 @@
+pub mod common;
+
+use common::{TEST_CLIENT_IP, test_headers};
+use plausible_rs::test_util::{MockPlausible, MockResponse};
+use plausible_rs::{Error, EventOutcome, EventPayload, EventSink, PAGEVIEW_EVENT, PropValue};
+use reqwest::StatusCode;
+use std::collections::HashMap;
+use std::time::Duration;
+
+fn payload(name: &str, props: Option<HashMap<String, PropValue>>) -> EventPayload {
+    let mut payload: EventPayload = EventPayload::builder(
+        String::from("example.com"),
+        name.to_string(),
+        String::from("https://example.com/signup"),
+    )
+    .build();
+    payload.props = props;
+    payload
+}
+
+#[tokio::test]
+async fn test_assertions() {
+    let plausible: MockPlausible = MockPlausible::new();
+    plausible.assert_no_events();
+
+    plausible
+        .event(test_headers(), payload(PAGEVIEW_EVENT, None))
+        .await
+        .unwrap();
+    let props: HashMap<String, PropValue> = HashMap::from([
+        (String::from("plan"), PropValue::from(String::from("pro"))),
+        (String::from("seats"), PropValue::from(3_u8)),
+    ]);
+    let outcome: EventOutcome = plausible
+        .event(test_headers(), payload("Signup", Some(props)))
+        .await
+        .unwrap();
+    assert!(outcome.is_accepted());
+
+    plausible.assert_event_count(2);
+    let record = plausible.assert_event_received(PAGEVIEW_EVENT);
+    assert_eq!(record.headers.x_forwarded_for, TEST_CLIENT_IP);
+    plausible.assert_event_with_prop("Signup", "plan", String::from("pro"));
+
+    // numbers are compared as they are sent, regardless of their Rust type
+    plausible.assert_event_with_prop("Signup", "seats", 3_i64);
+
+    plausible.clear();
+    plausible.assert_no_events();
+}
+
+#[tokio::test]
+#[should_panic(expected = "expected an event named \"Signup\" with prop \"plan\"")]
+async fn test_assert_event_with_prop_fails() {
+    let plausible: MockPlausible = MockPlausible::new();
+    let props: HashMap<String, PropValue> =
+        HashMap::from([(String::from("plan"), PropValue::from(String::from("free")))]);
+    plausible
+        .event(test_headers(), payload("Signup", Some(props)))
+        .await
+        .unwrap();
+
+    plausible.assert_event_with_prop("Signup", "plan", String::from("pro"));
+}
+
+#[tokio::test]
+async fn test_wait_for_events() {
+    let plausible: MockPlausible = MockPlausible::new();
+    let background: MockPlausible = plausible.clone();
+    tokio::spawn(async move {
+        for name in ["Signup", "Purchase"] {
+            background
+                .event(test_headers(), payload(name, None))
+                .await
+                .unwrap();
+        }
+    });
+
+    let events = plausible.wait_for_events(2, Duration::from_secs(5)).await;
+    assert_eq!(events[1].payload.name, "Purchase");
+}
+
+#[tokio::test(start_paused = true)]
+#[should_panic(expected = "expected 1 events within 1s, received []")]
+async fn test_wait_for_events_times_out() {
+    MockPlausible::new()
+        .wait_for_events(1, Duration::from_secs(1))
+        .await;
+}
+
+#[tokio::test]
+async fn test_scripted_responses() {
+    let plausible: MockPlausible = MockPlausible::new();
+    plausible
+        .respond_to_next_event(MockResponse::Dropped)
+        .respond_to_next_event(MockResponse::failed(StatusCode::TOO_MANY_REQUESTS));
+    plausible.respond_to_next_health(MockResponse::failed(StatusCode::SERVICE_UNAVAILABLE));
+
+    // responses are used in order
+    let outcome: EventOutcome = plausible
+        .event(test_headers(), payload(PAGEVIEW_EVENT, None))
+        .await
+        .unwrap();
+    assert!(outcome.response().unwrap().is_dropped());
+
+    let error: Error = plausible
+        .event(test_headers(), payload(PAGEVIEW_EVENT, None))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        Error::RequestFailed {
+            status_code: StatusCode::TOO_MANY_REQUESTS,
+            ..
+        }
+    ));
+
+    // then events are accepted again
+    assert!(
+        plausible
+            .event(test_headers(), payload(PAGEVIEW_EVENT, None))
+            .await
+            .unwrap()
+            .is_accepted()
+    );
+
+    // failed events are recorded too
+    plausible.assert_event_count(3);
+
+    assert!(plausible.health().await.is_err());
+    assert_eq!(plausible.health().await.unwrap().clickhouse, "ok");
+}
+
+#[tokio::test]
+async fn test_event_sink() {
+    let plausible: MockPlausible = MockPlausible::new();
+    let sink: &dyn EventSink = &plausible;
+    sink.send(test_headers(), payload(PAGEVIEW_EVENT, None))
+        .await
+        .unwrap();
+
+    plausible.assert_event_received(PAGEVIEW_EVENT);
+}
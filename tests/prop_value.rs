@@ -0,0 +1,60 @@
+use plausible_rs::PropValue;
+use serde_json::json;
+use std::collections::HashMap;
+
+#[test]
+fn test_serialize() {
+    // Plausible only accepts scalar props, so values are serialized without their variant
+    assert_eq!(
+        serde_json::to_value(PropValue::from(String::from("Todd"))).unwrap(),
+        json!("Todd")
+    );
+    assert_eq!(
+        serde_json::to_value(PropValue::from(true)).unwrap(),
+        json!(true)
+    );
+    assert_eq!(
+        serde_json::to_value(PropValue::from(2025_u16)).unwrap(),
+        json!(2025)
+    );
+    assert_eq!(
+        serde_json::to_value(PropValue::from(-1_i64)).unwrap(),
+        json!(-1)
+    );
+    assert_eq!(
+        serde_json::to_value(PropValue::from(0.5_f64)).unwrap(),
+        json!(0.5)
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    let props: HashMap<String, PropValue> = HashMap::from([
+        (
+            String::from("author"),
+            PropValue::from(String::from("Todd")),
+        ),
+        (String::from("logged_in"), PropValue::from(true)),
+        (String::from("year"), PropValue::from(2025_u16)),
+        (String::from("offset"), PropValue::from(-1_i64)),
+        (String::from("sample_rate"), PropValue::from(0.1_f64)),
+    ]);
+
+    // e.g. events written by a `FileSink` and read back, keep their values
+    let json: String = serde_json::to_string(&props).unwrap();
+    let deserialized: HashMap<String, PropValue> = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        serde_json::to_value(&deserialized).unwrap(),
+        serde_json::to_value(&props).unwrap()
+    );
+    assert!(matches!(deserialized["author"], PropValue::String(ref author) if author == "Todd"));
+    assert!(matches!(deserialized["logged_in"], PropValue::Bool(true)));
+    assert!(matches!(deserialized["year"], PropValue::U16(2025)));
+    assert!(matches!(deserialized["offset"], PropValue::I8(-1)));
+
+    // floats are read as `f64`, rather than rounded to `f32`
+    match deserialized["sample_rate"] {
+        PropValue::F64(sample_rate) => assert_eq!(sample_rate.to_bits(), 0.1_f64.to_bits()),
+        ref value => panic!("unexpected sample rate {value:?}"),
+    }
+}
@@ -0,0 +1,146 @@
+pub mod common;
+
+use common::TEST_CLIENT_IP;
+use plausible_rs::integrations::rocket::{
+    CONFIG_KEY, PlausibleConfig, PlausibleFairing, PlausibleTracker,
+};
+use plausible_rs::test_util::PlausibleServer;
+use plausible_rs::{EventRecord, PAGEVIEW_EVENT};
+use rocket::error::ErrorKind;
+use rocket::http::uri::Host;
+use rocket::http::{Header, Status};
+use rocket::local::asynchronous::{Client, LocalRequest, LocalResponse};
+use rocket::response::content::RawHtml;
+use rocket::{Build, Config, Rocket, get, routes, uri};
+use std::time::Duration;
+
+#[get("/")]
+const fn home() -> RawHtml<&'static str> {
+    RawHtml("<h1>Home</h1>")
+}
+
+#[get("/api")]
+const fn api() -> &'static str {
+    "plain text"
+}
+
+#[get("/admin/users")]
+const fn admin() -> RawHtml<&'static str> {
+    RawHtml("<h1>Admin</h1>")
+}
+
+#[get("/signup")]
+async fn signup(tracker: PlausibleTracker) -> String {
+    tracker
+        .event(tracker.payload(String::from("Signup")))
+        .await
+        .unwrap();
+    tracker.request().url.clone()
+}
+
+#[get("/signup", rank = 2)]
+const fn signup_fallback() -> &'static str {
+    "forwarded"
+}
+
+fn rocket(server: &PlausibleServer) -> Rocket<Build> {
+    let figment = Config::figment().merge(("log_level", "off")).merge((
+        CONFIG_KEY,
+        PlausibleConfig {
+            domain: String::from("example.com"),
+            base_url: server.base_url(),
+            exclude: vec![String::from("/admin/**")],
+            trusted_proxies: vec![String::from("10.0.0.0/8")],
+        },
+    ));
+    rocket::custom(figment)
+        .attach(PlausibleFairing::new())
+        .mount("/", routes![home, api, admin, signup])
+}
+
+/// Sends a request for `path` through a load balancer at `peer`.
+async fn send<'c>(client: &'c Client, path: &'c str, peer: &str) -> LocalResponse<'c> {
+    let mut request: LocalRequest<'c> = client
+        .get(path)
+        .header(Header::new("X-Real-IP", TEST_CLIENT_IP))
+        .header(Header::new("X-Forwarded-Proto", "https"))
+        .remote(peer.parse().unwrap());
+    request
+        .inner_mut()
+        .set_host(Host::from(uri!("example.com")));
+    request.dispatch().await
+}
+
+async fn get_page<'c>(client: &'c Client, path: &'c str) -> LocalResponse<'c> {
+    send(client, path, "10.0.0.1:51234").await
+}
+
+#[rocket::async_test]
+async fn test_fairing() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    let client: Client = Client::tracked(rocket(&server)).await.unwrap();
+
+    for path in ["/api", "/admin/users", "/missing", "/"] {
+        get_page(&client, path).await;
+    }
+
+    // pageviews are sent in the background
+    let events: Vec<EventRecord> = server
+        .mock()
+        .wait_for_events(1, Duration::from_secs(5))
+        .await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].payload.name, PAGEVIEW_EVENT);
+    assert_eq!(events[0].payload.url, "https://example.com/");
+    assert_eq!(events[0].headers.x_forwarded_for, TEST_CLIENT_IP);
+}
+
+#[rocket::async_test]
+async fn test_tracker() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    let client: Client = Client::tracked(rocket(&server)).await.unwrap();
+
+    let response: LocalResponse<'_> = get_page(&client, "/signup?plan=pro").await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        response.into_string().await.unwrap(),
+        "https://example.com/signup?plan=pro"
+    );
+    server.mock().assert_event_received("Signup");
+}
+
+#[rocket::async_test]
+async fn test_missing_config() {
+    let rocket: Rocket<Build> = rocket::custom(Config::figment().merge(("log_level", "off")))
+        .attach(PlausibleFairing::new());
+    assert!(matches!(
+        rocket.ignite().await.unwrap_err().kind(),
+        ErrorKind::FailedFairings(_)
+    ));
+}
+
+#[rocket::async_test]
+async fn test_untrusted_peer() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    let client: Client = Client::tracked(rocket(&server)).await.unwrap();
+
+    // `X-Forwarded-Proto` sent by clients themselves is ignored
+    let response: LocalResponse<'_> = send(&client, "/signup", "198.51.100.1:51234").await;
+    assert_eq!(
+        response.into_string().await.unwrap(),
+        "http://example.com/signup"
+    );
+}
+
+#[rocket::async_test]
+async fn test_missing_fairing() {
+    let rocket: Rocket<Build> = rocket::custom(Config::figment().merge(("log_level", "off")))
+        .mount("/", routes![signup, signup_fallback]);
+    let client: Client = Client::tracked(rocket).await.unwrap();
+
+    // fails instead of forwarding to the next route
+    assert_eq!(
+        send(&client, "/signup", "10.0.0.1:51234").await.status(),
+        Status::InternalServerError
+    );
+}
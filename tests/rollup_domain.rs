@@ -0,0 +1,195 @@
+use plausible_rs::test_util::PlausibleServer;
+use plausible_rs::{
+    Error, EventHeaders, EventOutcome, EventPayload, PAGEVIEW_EVENT, Plausible, SiteDomain,
+    UrlFilter,
+};
+use serde_json::{Value, json};
+
+#[test]
+fn test_add_domain() {
+    let rollup: SiteDomain = SiteDomain::new("rollup.example.com").unwrap();
+
+    let mut payload: EventPayload = EventPayload::builder(
+        String::from("example.com"),
+        PAGEVIEW_EVENT.to_string(),
+        String::from("https://example.com/test"),
+    )
+    .add_domain(&SiteDomain::new("shop.example.com").unwrap())
+    .add_domain(&rollup)
+    .build();
+
+    // domains are only added once
+    payload.add_domain(&rollup);
+    assert_eq!(
+        payload.domains().collect::<Vec<&str>>(),
+        vec!["example.com", "shop.example.com", "rollup.example.com"]
+    );
+
+    // Plausible expects a single comma-separated string
+    let value: Value = serde_json::to_value(payload).unwrap();
+    assert_eq!(
+        value["domain"],
+        json!("example.com,shop.example.com,rollup.example.com")
+    );
+}
+
+#[test]
+fn test_builder_for_domains() {
+    let payload: EventPayload = EventPayload::builder_for_domains(
+        &[
+            SiteDomain::new("example.com").unwrap(),
+            SiteDomain::new("shop.example.com").unwrap(),
+            SiteDomain::new("example.com").unwrap(),
+        ],
+        PAGEVIEW_EVENT.to_string(),
+        String::from("https://example.com/test"),
+    )
+    .unwrap()
+    .build();
+    assert_eq!(payload.domain, "example.com,shop.example.com");
+
+    // events must be recorded for at least one site
+    assert!(matches!(
+        EventPayload::builder_for_domains(
+            &[],
+            PAGEVIEW_EVENT.to_string(),
+            String::from("https://example.com/test"),
+        ),
+        Err(Error::InvalidSiteDomain(_))
+    ));
+}
+
+#[test]
+fn test_add_domain_to_empty() {
+    let mut payload: EventPayload = EventPayload::builder(
+        String::new(),
+        PAGEVIEW_EVENT.to_string(),
+        String::from("https://example.com/test"),
+    )
+    .build();
+    payload.add_domain(&SiteDomain::new("example.com").unwrap());
+    assert_eq!(payload.domain, "example.com");
+}
+
+#[test]
+fn test_add_domain_normalizes() {
+    let mut payload: EventPayload = EventPayload::builder(
+        String::from("example.com, ,shop.example.com,"),
+        PAGEVIEW_EVENT.to_string(),
+        String::from("https://example.com/test"),
+    )
+    .build();
+    payload.add_domain(&SiteDomain::new("rollup.example.com").unwrap());
+    assert_eq!(
+        payload.domain,
+        "example.com,shop.example.com,rollup.example.com"
+    );
+}
+
+#[tokio::test]
+async fn test_event_rollup_domain() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    let plausible: Plausible = Plausible::builder()
+        .base_url(server.base_url())
+        .rollup_domain(SiteDomain::new("rollup.example.com").unwrap())
+        .build();
+
+    plausible
+        .event(
+            EventHeaders::new(String::from("Mozilla/5.0"), String::from("127.0.0.1")),
+            EventPayload::builder(
+                String::from("example.com,"),
+                PAGEVIEW_EVENT.to_string(),
+                String::from("https://example.com/test"),
+            )
+            .build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        server
+            .mock()
+            .assert_event_received(PAGEVIEW_EVENT)
+            .payload
+            .domain,
+        "example.com,rollup.example.com"
+    );
+}
+
+#[tokio::test]
+async fn test_event_url_filter_per_domain() {
+    let mut admin_excluded: UrlFilter = UrlFilter::new();
+    admin_excluded.exclude("/admin/**");
+    let mut blog_only: UrlFilter = UrlFilter::new();
+    blog_only.include("/blog/**");
+
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    let plausible: Plausible = Plausible::builder()
+        .base_url(server.base_url())
+        .url_filter(String::from("example.com"), admin_excluded)
+        .url_filter(String::from("rollup.example.com"), blog_only)
+        .rollup_domain(SiteDomain::new("rollup.example.com").unwrap())
+        .build();
+    let event = |url: &str| {
+        plausible.event(
+            EventHeaders::new(String::from("Mozilla/5.0"), String::from("127.0.0.1")),
+            EventPayload::builder_for_domains(
+                &[
+                    SiteDomain::new("example.com").unwrap(),
+                    SiteDomain::new("shop.example.com").unwrap(),
+                ],
+                PAGEVIEW_EVENT.to_string(),
+                url.to_string(),
+            )
+            .unwrap()
+            .build(),
+        )
+    };
+
+    // each site's filter only removes that site, including the roll-up site's
+    event("https://example.com/admin/users").await.unwrap();
+    event("https://example.com/blog/hello").await.unwrap();
+    let domains: Vec<String> = server
+        .mock()
+        .events()
+        .into_iter()
+        .map(|record| record.payload.domain)
+        .collect();
+    assert_eq!(
+        domains,
+        vec![
+            "shop.example.com",
+            "example.com,shop.example.com,rollup.example.com",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_event_excluded_for_every_domain() {
+    let mut url_filter: UrlFilter = UrlFilter::new();
+    url_filter.exclude("/admin/**");
+
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    let plausible: Plausible = Plausible::builder()
+        .base_url(server.base_url())
+        .url_filter(String::from("example.com"), url_filter.clone())
+        .url_filter(String::from("rollup.example.com"), url_filter)
+        .rollup_domain(SiteDomain::new("rollup.example.com").unwrap())
+        .build();
+
+    // the event is only dropped once no site is left
+    let outcome: EventOutcome = plausible
+        .event(
+            EventHeaders::new(String::from("Mozilla/5.0"), String::from("127.0.0.1")),
+            EventPayload::builder(
+                String::from("example.com"),
+                PAGEVIEW_EVENT.to_string(),
+                String::from("https://example.com/admin/users"),
+            )
+            .build(),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(outcome, EventOutcome::Excluded), "{outcome:?}");
+    assert!(server.mock().events().is_empty());
+}
@@ -0,0 +1,73 @@
+use plausible_rs::{
+    EventHeaders, EventOutcome, EventPayload, Plausible, PropValue, SamplingPolicy,
+};
+use std::collections::HashMap;
+
+const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/105.0.0.0 Safari/537.36";
+
+fn payload(name: &str) -> EventPayload {
+    EventPayload::builder(
+        String::from("example.com"),
+        name.to_string(),
+        String::from("https://example.com/search"),
+    )
+    .build()
+}
+
+#[test]
+fn test_sample() {
+    let mut policy: SamplingPolicy =
+        SamplingPolicy::new(HashMap::from([(String::from("Search Keystroke"), 0.25)]));
+    policy.rate_prop = Some(String::from("sample_rate"));
+
+    // count the visitors that are kept
+    let mut kept: usize = 0;
+    for i in 0..1000 {
+        let headers: EventHeaders = EventHeaders::new(
+            USER_AGENT.to_string(),
+            format!("10.0.{}.{}", i / 256, i % 256),
+        );
+        let mut payload: EventPayload = payload("Search Keystroke");
+
+        let sampled: bool = policy.sample(&headers, &mut payload);
+
+        // a visitor is consistently in or out
+        assert_eq!(sampled, policy.sample(&headers, &mut payload.clone()));
+
+        if sampled {
+            kept += 1;
+            let props = payload.props.unwrap();
+            assert!(
+                matches!(props["sample_rate"], PropValue::F64(rate) if (rate - 0.25).abs() < f64::EPSILON)
+            );
+        }
+    }
+    assert!((200..300).contains(&kept), "kept {kept} of 1000 events");
+
+    // events without a rate are always kept, and left untouched
+    let headers: EventHeaders =
+        EventHeaders::new(USER_AGENT.to_string(), String::from("127.0.0.1"));
+    let mut payload: EventPayload = payload("pageview");
+    assert!(policy.sample(&headers, &mut payload));
+    assert!(payload.props.is_none());
+}
+
+#[tokio::test]
+async fn test_event_sampled() {
+    let plausible: Plausible = Plausible::builder()
+        .base_url(String::from("http://127.0.0.1:1"))
+        .sampling(SamplingPolicy::new(HashMap::from([(
+            String::from("Search Keystroke"),
+            0.0,
+        )])))
+        .build();
+
+    // post Event, which is dropped before being sent
+    let headers: EventHeaders =
+        EventHeaders::new(USER_AGENT.to_string(), String::from("127.0.0.1"));
+    let outcome: EventOutcome = plausible
+        .event(headers, payload("Search Keystroke"))
+        .await
+        .unwrap();
+    assert!(matches!(outcome, EventOutcome::Sampled));
+}
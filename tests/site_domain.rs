@@ -0,0 +1,74 @@
+use plausible_rs::{Error, EventPayload, PAGEVIEW_EVENT, SiteDomain};
+use url::Url;
+
+#[test]
+fn test_new() {
+    assert_eq!(
+        SiteDomain::new(" Example.com ").unwrap().as_str(),
+        "example.com"
+    );
+    assert_eq!(SiteDomain::new("My Mobile App").ok(), None);
+    assert_eq!(
+        SiteDomain::new("my-mobile-app").unwrap().as_str(),
+        "my-mobile-app"
+    );
+
+    for domain in ["", "https://example.com", "example.com/blog", "a.com,b.com"] {
+        assert!(matches!(
+            SiteDomain::new(domain),
+            Err(Error::InvalidSiteDomain(_))
+        ));
+    }
+}
+
+#[test]
+fn test_matches_host() {
+    let domain: SiteDomain = SiteDomain::new("example.com").unwrap();
+    assert!(domain.matches_host("example.com"));
+    assert!(domain.matches_host("www.example.com"));
+    assert!(domain.matches_host("blog.example.com"));
+    assert!(domain.matches_host("localhost"));
+    assert!(domain.matches_host("127.0.0.1"));
+    assert!(!domain.matches_host("example.org"));
+    assert!(!domain.matches_host("notexample.com"));
+}
+
+#[test]
+fn test_try_builder() {
+    let payload: EventPayload = EventPayload::try_builder(
+        "example.com",
+        PAGEVIEW_EVENT.to_string(),
+        "https://example.com/test",
+    )
+    .unwrap()
+    .referrer_url(&Url::parse("https://www.toddgriffin.me/").unwrap())
+    .build();
+    assert_eq!(payload.domain, "example.com");
+    assert_eq!(payload.url, "https://example.com/test");
+    assert_eq!(
+        payload.referrer.as_deref(),
+        Some("https://www.toddgriffin.me/")
+    );
+
+    // relative URLs and typos are caught before sending
+    assert!(matches!(
+        EventPayload::try_builder("example.com", PAGEVIEW_EVENT.to_string(), "/test"),
+        Err(Error::UrlError(_))
+    ));
+    assert!(matches!(
+        EventPayload::try_builder(
+            "example.com",
+            PAGEVIEW_EVENT.to_string(),
+            "https//example.com"
+        ),
+        Err(Error::UrlError(_))
+    ));
+    assert!(matches!(
+        EventPayload::try_builder(
+            "example.com",
+            PAGEVIEW_EVENT.to_string(),
+            "mailto:hi@example.com"
+        ),
+        Err(Error::InvalidUrl(_))
+    ));
+}
@@ -0,0 +1,51 @@
+use plausible_rs::{
+    EventPayload, FILE_DOWNLOAD_EVENT, FORM_SUBMISSION_EVENT, NOT_FOUND_EVENT,
+    OUTBOUND_LINK_CLICK_EVENT,
+};
+use serde_json::{Value, json};
+
+#[test]
+fn test_serialize() {
+    let payload: EventPayload = EventPayload::outbound_link(
+        String::from("example.com"),
+        String::from("https://example.com/blog"),
+        String::from("https://www.toddgriffin.me/"),
+    );
+    assert_eq!(payload.name, OUTBOUND_LINK_CLICK_EVENT);
+    assert_eq!(
+        serde_json::to_value(payload).unwrap(),
+        json!({
+            "domain": "example.com",
+            "name": "Outbound Link: Click",
+            "url": "https://example.com/blog",
+            "props": { "url": "https://www.toddgriffin.me/" },
+        })
+    );
+
+    let payload: EventPayload = EventPayload::file_download(
+        String::from("example.com"),
+        String::from("https://example.com/docs"),
+        String::from("https://example.com/manual.pdf"),
+    );
+    assert_eq!(payload.name, FILE_DOWNLOAD_EVENT);
+    let value: Value = serde_json::to_value(payload).unwrap();
+    assert_eq!(
+        value["props"],
+        json!({ "url": "https://example.com/manual.pdf" })
+    );
+
+    let payload: EventPayload = EventPayload::not_found(
+        String::from("example.com"),
+        String::from("https://example.com/missing?ref=twitter"),
+    );
+    assert_eq!(payload.name, NOT_FOUND_EVENT);
+    let value: Value = serde_json::to_value(payload).unwrap();
+    assert_eq!(value["props"], json!({ "path": "/missing" }));
+
+    let payload: EventPayload = EventPayload::form_submission(
+        String::from("example.com"),
+        String::from("https://example.com/contact"),
+    );
+    assert_eq!(payload.name, FORM_SUBMISSION_EVENT);
+    assert!(payload.props.is_none());
+}
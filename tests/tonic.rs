@@ -0,0 +1,159 @@
+pub mod common;
+
+use bytes::Bytes;
+use common::TEST_CLIENT_IP;
+use http::{HeaderMap, Request, Response};
+use http_body_util::combinators::WithTrailers;
+use http_body_util::{BodyExt, Full};
+use plausible_rs::integrations::tonic::{PlausibleLayer, RPC_EVENT, STATUS_PROP};
+use plausible_rs::test_util::PlausibleServer;
+use plausible_rs::{EventRecord, Plausible, PropValue};
+use std::convert::Infallible;
+use std::future::{Ready, ready};
+use std::time::Duration;
+use tonic::transport::server::TcpConnectInfo;
+use tower::{Layer, ServiceExt, service_fn};
+
+type TestBody = WithTrailers<Full<Bytes>, Ready<Option<Result<HeaderMap, Infallible>>>>;
+
+/// Answers `/helloworld.Greeter/SayHello` with a message and an `Ok` trailer, and anything else
+/// with a trailers-only `NotFound` response.
+async fn greeter(request: Request<()>) -> Result<Response<TestBody>, Infallible> {
+    let mut trailers: HeaderMap = HeaderMap::new();
+    trailers.insert("grpc-status", "0".parse().unwrap());
+    let body: TestBody = Full::new(Bytes::from("hello")).with_trailers(ready(Some(Ok(trailers))));
+
+    if request.uri().path() == "/helloworld.Greeter/SayHello" {
+        Ok(Response::new(body))
+    } else {
+        let mut response: Response<TestBody> = Response::new(body);
+        response
+            .headers_mut()
+            .insert("grpc-status", "5".parse().unwrap());
+        Ok(response)
+    }
+}
+
+#[tokio::test]
+async fn test_rpcs() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    let layer: PlausibleLayer = PlausibleLayer::new(server.client(), String::from("example.com"));
+
+    for path in [
+        "/grpc.health.v1.Health/Check",
+        "/helloworld.Greeter/SayHello",
+        "/helloworld.Greeter/SayGoodbye",
+    ] {
+        let request: Request<()> = Request::post(path)
+            .header("user-agent", "grpc-rust/0.14")
+            .header("x-forwarded-for", TEST_CLIENT_IP)
+            .body(())
+            .unwrap();
+        let response = layer
+            .layer(service_fn(greeter))
+            .oneshot(request)
+            .await
+            .unwrap();
+        response.into_body().collect().await.unwrap();
+    }
+
+    let mut events: Vec<EventRecord> = server
+        .mock()
+        .wait_for_events(2, Duration::from_secs(5))
+        .await;
+    events.sort_by(|a, b| a.payload.url.cmp(&b.payload.url));
+    assert_eq!(events.len(), 2);
+    assert!(events.iter().all(|event| event.payload.name == RPC_EVENT
+        && event.headers.user_agent == "grpc-rust/0.14"
+        && event.headers.x_forwarded_for == TEST_CLIENT_IP));
+
+    let status = |event: &EventRecord| match &event.payload.props.as_ref().unwrap()[STATUS_PROP] {
+        PropValue::String(status) => status.clone(),
+        value => panic!("unexpected status {value:?}"),
+    };
+    assert_eq!(
+        events[0].payload.url,
+        "app://example.com/helloworld.Greeter/SayGoodbye"
+    );
+    assert_eq!(status(&events[0]), "NotFound");
+    assert_eq!(
+        events[1].payload.url,
+        "app://example.com/helloworld.Greeter/SayHello"
+    );
+    assert_eq!(status(&events[1]), "Ok");
+}
+
+#[tokio::test]
+async fn test_cancelled() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    let layer: PlausibleLayer = PlausibleLayer::new(server.client(), String::from("example.com"));
+
+    let request: Request<()> = Request::post("/helloworld.Greeter/SayHello")
+        .header("x-forwarded-for", TEST_CLIENT_IP)
+        .body(())
+        .unwrap();
+    let response = layer
+        .layer(service_fn(greeter))
+        .oneshot(request)
+        .await
+        .unwrap();
+    drop(response);
+
+    let events: Vec<EventRecord> = server
+        .mock()
+        .wait_for_events(1, Duration::from_secs(5))
+        .await;
+    assert_eq!(events.len(), 1);
+    server
+        .mock()
+        .assert_event_with_prop(RPC_EVENT, STATUS_PROP, String::from("Cancelled"));
+}
+
+#[test]
+fn test_dropped_outside_runtime() {
+    let layer: PlausibleLayer = PlausibleLayer::new(
+        Plausible::builder()
+            .base_url(String::from("http://127.0.0.1:1"))
+            .build(),
+        String::from("example.com"),
+    );
+
+    let request: Request<()> = Request::post("/helloworld.Greeter/SayHello")
+        .header("x-forwarded-for", TEST_CLIENT_IP)
+        .body(())
+        .unwrap();
+    let response = tokio::runtime::Runtime::new()
+        .unwrap()
+        .block_on(layer.layer(service_fn(greeter)).oneshot(request))
+        .unwrap();
+
+    // the cancelled RPC can't be recorded without a runtime, but dropping its body doesn't panic
+    drop(response);
+}
+
+#[tokio::test]
+async fn test_connect_info() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    let layer: PlausibleLayer = PlausibleLayer::new(server.client(), String::from("example.com"));
+
+    // a direct connection, without forwarding metadata
+    let mut request: Request<()> = Request::post("/helloworld.Greeter/SayHello")
+        .body(())
+        .unwrap();
+    request.extensions_mut().insert(TcpConnectInfo {
+        local_addr: None,
+        remote_addr: Some("198.51.100.1:51234".parse().unwrap()),
+    });
+    let response = layer
+        .layer(service_fn(greeter))
+        .oneshot(request)
+        .await
+        .unwrap();
+    response.into_body().collect().await.unwrap();
+
+    let events: Vec<EventRecord> = server
+        .mock()
+        .wait_for_events(1, Duration::from_secs(5))
+        .await;
+    assert_eq!(events[0].headers.x_forwarded_for, "198.51.100.1");
+}
@@ -0,0 +1,167 @@
+use http::{HeaderMap, HeaderValue};
+use plausible_rs::test_util::PlausibleServer;
+use plausible_rs::{
+    CF_CONNECTING_IP, Error, EventHeaders, EventPayload, PAGEVIEW_EVENT, PRIVATE_NETWORKS,
+    Plausible, TrustedProxies,
+};
+use std::net::IpAddr;
+
+fn header_map(headers: &[(&'static str, &'static str)]) -> HeaderMap {
+    let mut map: HeaderMap = HeaderMap::new();
+    for (name, value) in headers {
+        map.append(*name, HeaderValue::from_static(value));
+    }
+    map
+}
+
+fn ip(ip: &str) -> IpAddr {
+    ip.parse().unwrap()
+}
+
+#[test]
+fn test_chain() {
+    // a spoofed address, the client, then a load balancer
+    let headers: HeaderMap = header_map(&[("x-forwarded-for", "1.2.3.4, 203.0.113.7, 10.0.0.2")]);
+    let peer: Option<IpAddr> = Some(ip("10.0.0.1"));
+
+    // trusts nothing by default
+    assert_eq!(
+        TrustedProxies::new().client_ip(&headers, peer).unwrap(),
+        ip("10.0.0.1")
+    );
+
+    // by network
+    let trusted_proxies: TrustedProxies = TrustedProxies::with_cidrs(PRIVATE_NETWORKS).unwrap();
+    assert_eq!(
+        trusted_proxies.client_ip(&headers, peer).unwrap(),
+        ip("203.0.113.7")
+    );
+
+    // by hop count
+    assert_eq!(
+        TrustedProxies::new()
+            .hops(2)
+            .client_ip(&headers, peer)
+            .unwrap(),
+        ip("203.0.113.7")
+    );
+
+    // every address is trusted
+    assert_eq!(
+        TrustedProxies::new()
+            .hops(10)
+            .client_ip(&headers, peer)
+            .unwrap(),
+        ip("1.2.3.4")
+    );
+
+    let headers: EventHeaders =
+        EventHeaders::from_header_map_trusting(&headers, peer, &trusted_proxies).unwrap();
+    assert_eq!(headers.x_forwarded_for, "203.0.113.7");
+
+    assert!(matches!(
+        TrustedProxies::with_cidrs(&["10.0.0.0/33"]),
+        Err(Error::InvalidCidr(_))
+    ));
+    assert!(matches!(
+        TrustedProxies::new().client_ip(&HeaderMap::new(), None),
+        Err(Error::MissingClientIp)
+    ));
+}
+
+#[test]
+fn test_presets() {
+    let headers: HeaderMap = header_map(&[
+        ("cf-connecting-ip", "203.0.113.7"),
+        ("x-real-ip", "203.0.113.8"),
+        ("true-client-ip", "203.0.113.9"),
+        ("fly-client-ip", "2001:db8::1"),
+        ("fastly-client-ip", "203.0.113.10"),
+        ("x-forwarded-for", "198.51.100.1"),
+    ]);
+    let cloudflare_peer: Option<IpAddr> = Some(ip("173.245.48.1"));
+    let proxy_peer: Option<IpAddr> = Some(ip("10.0.0.1"));
+
+    for (trusted_proxies, peer, expected) in [
+        (
+            TrustedProxies::cloudflare(&["173.245.48.0/20"]),
+            cloudflare_peer,
+            "203.0.113.7",
+        ),
+        (
+            TrustedProxies::x_real_ip(PRIVATE_NETWORKS),
+            proxy_peer,
+            "203.0.113.8",
+        ),
+        (
+            TrustedProxies::true_client_ip(&["173.245.48.0/20"]),
+            cloudflare_peer,
+            "203.0.113.9",
+        ),
+        (
+            TrustedProxies::fly(PRIVATE_NETWORKS),
+            proxy_peer,
+            "2001:db8::1",
+        ),
+        (
+            TrustedProxies::fastly(&["173.245.48.0/20"]),
+            cloudflare_peer,
+            "203.0.113.10",
+        ),
+    ] {
+        assert_eq!(
+            trusted_proxies.unwrap().client_ip(&headers, peer).unwrap(),
+            ip(expected)
+        );
+    }
+
+    // the header is ignored if the peer isn't a trusted proxy, as the client may have set it
+    let cloudflare: TrustedProxies = TrustedProxies::cloudflare(&["173.245.48.0/20"]).unwrap();
+    assert_eq!(
+        cloudflare
+            .client_ip(&headers, Some(ip("192.0.2.1")))
+            .unwrap(),
+        ip("192.0.2.1")
+    );
+
+    // and always ignored without trusted networks
+    let mut no_cidrs: TrustedProxies = TrustedProxies::new();
+    no_cidrs.client_ip_header(CF_CONNECTING_IP);
+    assert_eq!(
+        no_cidrs.client_ip(&headers, cloudflare_peer).unwrap(),
+        ip("173.245.48.1")
+    );
+
+    assert!(matches!(
+        TrustedProxies::cloudflare(&["not a network"]),
+        Err(Error::InvalidCidr(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_client_rewrites_x_forwarded_for() {
+    let server: PlausibleServer = PlausibleServer::start().await.unwrap();
+    let plausible: Plausible = Plausible::builder()
+        .base_url(server.base_url())
+        .trusted_proxies(TrustedProxies::with_cidrs(&["10.0.0.0/8"]).unwrap())
+        .build();
+
+    plausible
+        .event(
+            EventHeaders::new(
+                String::from("Mozilla/5.0"),
+                String::from("1.2.3.4, 203.0.113.7, 10.0.0.2"),
+            ),
+            EventPayload::builder(
+                String::from("example.com"),
+                PAGEVIEW_EVENT.to_string(),
+                String::from("https://example.com/"),
+            )
+            .build(),
+        )
+        .await
+        .unwrap();
+
+    let events = server.mock().events();
+    assert_eq!(events[0].headers.x_forwarded_for, "203.0.113.7");
+}
@@ -0,0 +1,73 @@
+use plausible_rs::{
+    EventHeaders, EventOutcome, EventPayload, PAGEVIEW_EVENT, Plausible, UrlFilter,
+};
+
+#[test]
+fn test_exclude() {
+    let mut url_filter: UrlFilter = UrlFilter::new();
+    url_filter
+        .exclude("/blog4")
+        .exclude("/rule/*")
+        .exclude("/how-to-*")
+        .exclude("/*/admin/**");
+
+    // exact paths, with an optional trailing slash
+    assert!(!url_filter.is_path_tracked("/blog4"));
+    assert!(!url_filter.is_path_tracked("/blog4/"));
+    assert!(url_filter.is_path_tracked("/blog45"));
+
+    // `*` stays within a path segment
+    assert!(!url_filter.is_path_tracked("/rule/1"));
+    assert!(url_filter.is_path_tracked("/rule/1/2"));
+    assert!(!url_filter.is_path_tracked("/how-to-fly"));
+    assert!(url_filter.is_path_tracked("/how-to/fly"));
+
+    // `**` crosses path segments
+    assert!(!url_filter.is_path_tracked("/en/admin/users/42"));
+    assert!(url_filter.is_path_tracked("/en/de/admin/users"));
+
+    // the path is taken from the URL, ignoring the query
+    assert!(!url_filter.is_tracked("https://example.com/rule/1?page=2"));
+    assert!(url_filter.is_tracked("https://example.com/?page=/rule/1"));
+}
+
+#[test]
+fn test_include() {
+    let mut url_filter: UrlFilter = UrlFilter::new();
+    url_filter.include("/docs/**").exclude("/docs/internal/**");
+
+    assert!(url_filter.is_path_tracked("/docs/getting-started"));
+    assert!(!url_filter.is_path_tracked("/docs/internal/roadmap"));
+    assert!(!url_filter.is_path_tracked("/pricing"));
+}
+
+#[tokio::test]
+async fn test_event_excluded() {
+    let mut url_filter: UrlFilter = UrlFilter::new();
+    url_filter.exclude("/admin/**");
+
+    let plausible: Plausible = Plausible::builder()
+        .base_url(String::from("http://127.0.0.1:1"))
+        .url_filter(String::from("example.com"), url_filter)
+        .build();
+
+    // post Event, which is dropped before being sent
+    let outcome: EventOutcome = plausible
+        .event(
+            EventHeaders::new(
+                String::from(
+                    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/105.0.0.0 Safari/537.36",
+                ),
+                String::from("127.0.0.1"),
+            ),
+            EventPayload::builder(
+                String::from("example.com"),
+                PAGEVIEW_EVENT.to_string(),
+                String::from("https://example.com/admin/users"),
+            )
+            .build(),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(outcome, EventOutcome::Excluded));
+}
@@ -0,0 +1,68 @@
+use plausible_rs::{TrailingSlash, UrlNormalizer};
+
+#[test]
+fn test_query_params() {
+    let mut url_normalizer: UrlNormalizer = UrlNormalizer::new();
+
+    // UTM parameters are kept by default, everything else is removed
+    assert_eq!(
+        url_normalizer.normalize(
+            "https://example.com/login?session=s3cr3t&utm_source=newsletter&utm_campaign=spring#top"
+        ),
+        "https://example.com/login?utm_source=newsletter&utm_campaign=spring"
+    );
+    assert_eq!(
+        url_normalizer.normalize("https://example.com/login?session=s3cr3t"),
+        "https://example.com/login"
+    );
+
+    // more parameters can be allowed
+    url_normalizer.allow_query_param("page");
+    assert_eq!(
+        url_normalizer.normalize("https://example.com/blog?page=2&session=s3cr3t"),
+        "https://example.com/blog?page=2"
+    );
+
+    // or all of them
+    url_normalizer.allow_all_query_params();
+    assert_eq!(
+        url_normalizer.normalize("https://example.com/blog?page=2&session=s3cr3t"),
+        "https://example.com/blog?page=2&session=s3cr3t"
+    );
+}
+
+#[test]
+fn test_path() {
+    let mut url_normalizer: UrlNormalizer = UrlNormalizer::new();
+    url_normalizer
+        .route("/users/:id/settings")
+        .route("/orders/:order_id")
+        .lowercase(true)
+        .trailing_slash(TrailingSlash::Remove);
+
+    assert_eq!(
+        url_normalizer.normalize("https://example.com/users/8123/settings"),
+        "https://example.com/users/:id/settings"
+    );
+    assert_eq!(
+        url_normalizer.normalize("https://example.com/Orders/A-12/"),
+        "https://example.com/orders/:order_id"
+    );
+    assert_eq!(
+        url_normalizer.normalize("https://example.com/users/8123/"),
+        "https://example.com/users/8123"
+    );
+    assert_eq!(
+        url_normalizer.normalize("https://example.com/"),
+        "https://example.com/"
+    );
+
+    url_normalizer.trailing_slash(TrailingSlash::Add);
+    assert_eq!(
+        url_normalizer.normalize("app://localhost/login"),
+        "app://localhost/login/"
+    );
+
+    // URLs that can't be parsed are left as is
+    assert_eq!(url_normalizer.normalize("/relative"), "/relative");
+}
@@ -0,0 +1,48 @@
+use plausible_rs::{EventPayload, PAGEVIEW_EVENT, Utm};
+
+#[test]
+fn test_from_url() {
+    let utm: Utm = Utm::from_url(
+        "https://example.com/landing?utm_source=newsletter&utm_medium=email&utm_campaign=spring%20sale&id=1",
+    )
+    .unwrap();
+    assert_eq!(utm.source.as_deref(), Some("newsletter"));
+    assert_eq!(utm.medium.as_deref(), Some("email"));
+    assert_eq!(utm.campaign.as_deref(), Some("spring sale"));
+    assert_eq!(utm.content, None);
+    assert_eq!(utm.term, None);
+
+    // relative request URLs, and the `ref` alias
+    let utm: Utm = Utm::from_url("/landing?ref=producthunt").unwrap();
+    assert_eq!(utm.source.as_deref(), Some("producthunt"));
+
+    // `utm_source` takes precedence over its aliases
+    let utm: Utm = Utm::from_url("/landing?source=a&utm_source=b&ref=c").unwrap();
+    assert_eq!(utm.source.as_deref(), Some("b"));
+
+    assert!(Utm::from_url("/landing").unwrap().is_empty());
+}
+
+#[test]
+fn test_builder() {
+    let mut utm: Utm = Utm::new();
+    utm.source(String::from("newsletter"))
+        .campaign(String::from("spring sale"));
+
+    let payload: EventPayload = EventPayload::builder(
+        String::from("example.com"),
+        PAGEVIEW_EVENT.to_string(),
+        String::from("https://example.com/landing?id=1&ref=old&utm_medium=email"),
+    )
+    .utm(utm.clone())
+    .build();
+    assert_eq!(
+        payload.url,
+        "https://example.com/landing?id=1&utm_medium=email&utm_source=newsletter&utm_campaign=spring+sale"
+    );
+
+    // round trip
+    let mut expected: Utm = utm;
+    expected.medium(String::from("email"));
+    assert_eq!(Utm::from_url(&payload.url).unwrap(), expected);
+}